@@ -0,0 +1,37 @@
+// Copyright (c) 2026, The rav1e contributors. All rights reserved
+//
+// This source code is subject to the terms of the BSD 2 Clause License and
+// the Alliance for Open Media Patent License 1.0. If the BSD 2 Clause License
+// was not distributed with this source code in the LICENSE file, you can
+// obtain it at www.aomedia.org/license/software. If the Alliance for Open
+// Media Patent License 1.0 was not distributed with this source code in the
+// PATENTS file, you can obtain it at www.aomedia.org/license/patent.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use v_frame::frame::Frame;
+use v_frame::pixel::ChromaSampling;
+
+fn map(c: &mut Criterion) {
+    let mut frame = Frame::<u8>::new_with_padding(3840, 2160, ChromaSampling::Cs420, 64);
+    frame.fill(200);
+    let to_10_bit = |p: u8| (p as u16) << 2;
+
+    c.bench_function("map_4k_8_to_10_bit", |b| {
+        b.iter(|| black_box(&frame).map(to_10_bit))
+    });
+    c.bench_function("par_map_4k_8_to_10_bit", |b| {
+        b.iter(|| black_box(&frame).par_map(to_10_bit))
+    });
+}
+
+fn fill(c: &mut Criterion) {
+    let mut frame = Frame::<u16>::new_with_padding(3840, 2160, ChromaSampling::Cs420, 64);
+
+    c.bench_function("fill_4k_10_bit", |b| b.iter(|| frame.fill(black_box(512))));
+    c.bench_function("par_fill_4k_10_bit", |b| {
+        b.iter(|| frame.par_fill(black_box(512)))
+    });
+}
+
+criterion_group!(benches, map, fill);
+criterion_main!(benches);
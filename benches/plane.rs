@@ -0,0 +1,82 @@
+// Copyright (c) 2026, The rav1e contributors. All rights reserved
+//
+// This source code is subject to the terms of the BSD 2 Clause License and
+// the Alliance for Open Media Patent License 1.0. If the BSD 2 Clause License
+// was not distributed with this source code in the LICENSE file, you can
+// obtain it at www.aomedia.org/license/software. If the Alliance for Open
+// Media Patent License 1.0 was not distributed with this source code in the
+// PATENTS file, you can obtain it at www.aomedia.org/license/patent.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use v_frame::frame::Frame;
+use v_frame::pixel::ChromaSampling;
+use v_frame::plane::Plane;
+use v_frame::pool::FramePool;
+
+const WIDTH: usize = 3840;
+const HEIGHT: usize = 2160;
+
+fn planes() -> [(&'static str, Plane<u16>); 2] {
+    let mut unpadded = Plane::new(WIDTH, HEIGHT, 0, 0, 0, 0);
+    let mut padded = Plane::new(WIDTH, HEIGHT, 0, 0, 64, 64);
+    unpadded.fill(512);
+    padded.fill(512);
+
+    [("unpadded", unpadded), ("padded", padded)]
+}
+
+fn iteration(c: &mut Criterion) {
+    for (name, plane) in planes() {
+        c.bench_function(&format!("iter_4k_{}", name), |b| {
+            b.iter(|| black_box(&plane).iter().map(u64::from).sum::<u64>())
+        });
+        c.bench_function(&format!("rows_iter_4k_{}", name), |b| {
+            b.iter(|| {
+                black_box(&plane)
+                    .rows_iter()
+                    .map(|row| row.iter().map(|&p| p as u64).sum::<u64>())
+                    .sum::<u64>()
+            })
+        });
+    }
+}
+
+fn sad(c: &mut Criterion) {
+    let [(_, a), (_, mut b)] = planes();
+    b.fill(500);
+
+    c.bench_function("sad_4k", |bench| {
+        bench.iter(|| black_box(&a).sad(black_box(&b)))
+    });
+}
+
+fn copy_from(c: &mut Criterion) {
+    let [(_, unpadded), (_, padded)] = planes();
+    let mut dst = padded.clone();
+
+    c.bench_function("copy_from_4k_equal_stride", |b| {
+        b.iter(|| dst.copy_from(black_box(&padded)))
+    });
+    c.bench_function("copy_from_4k_different_stride", |b| {
+        b.iter(|| dst.copy_from(black_box(&unpadded)))
+    });
+}
+
+fn frame_pool(c: &mut Criterion) {
+    c.bench_function("new_frame_4k", |b| {
+        b.iter(|| Frame::<u16>::new_with_padding(WIDTH, HEIGHT, ChromaSampling::Cs420, 64))
+    });
+
+    let mut pool = FramePool::<u16>::new(1);
+    c.bench_function("pool_frame_4k", |b| {
+        b.iter(|| {
+            let frame = pool.get(WIDTH, HEIGHT, ChromaSampling::Cs420, 64);
+            pool.recycle(black_box(frame));
+        })
+    });
+    // every iteration after the first reuses the recycled frame
+    assert_eq!(pool.allocations(), 1);
+}
+
+criterion_group!(benches, iteration, sad, copy_from, frame_pool);
+criterion_main!(benches);
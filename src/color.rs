@@ -0,0 +1,448 @@
+// Copyright (c) 2026, The rav1e contributors. All rights reserved
+//
+// This source code is subject to the terms of the BSD 2 Clause License and
+// the Alliance for Open Media Patent License 1.0. If the BSD 2 Clause License
+// was not distributed with this source code in the LICENSE file, you can
+// obtain it at www.aomedia.org/license/software. If the Alliance for Open
+// Media Patent License 1.0 was not distributed with this source code in the
+// PATENTS file, you can obtain it at www.aomedia.org/license/patent.
+
+use crate::frame::Frame;
+use crate::pixel::{ChromaSampling, Pixel};
+use crate::plane::Plane;
+
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+/// Matrix coefficients used to convert between RGB and YCbCr.
+///
+/// Conversions assume limited ("studio") range YCbCr, i.e. luma in `16..=235`
+/// and chroma in `16..=240` for 8-bit samples.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColorMatrix {
+    /// ITU-R BT.601, used by standard definition content.
+    Bt601,
+    /// ITU-R BT.709, used by high definition content.
+    #[default]
+    Bt709,
+}
+
+impl ColorMatrix {
+    /// Returns the `(Kr, Kb)` luma coefficients of the matrix.
+    const fn coefficients(self) -> (f32, f32) {
+        match self {
+            ColorMatrix::Bt601 => (0.299, 0.114),
+            ColorMatrix::Bt709 => (0.2126, 0.0722),
+        }
+    }
+
+    /// Converts an 8-bit YCbCr triplet into an 8-bit RGB triplet.
+    pub fn ycbcr_to_rgb(self, y: u8, cb: u8, cr: u8) -> [u8; 3] {
+        let y = (y as f32 - 16.0) / 219.0;
+        let pb = (cb as f32 - 128.0) / 224.0;
+        let pr = (cr as f32 - 128.0) / 224.0;
+
+        self.ypbpr_to_rgb(y, pb, pr).map(|v| to_u8(v * 255.0))
+    }
+
+    /// Converts normalized luma in `0.0..=1.0` and color differences in
+    /// `-0.5..=0.5` into unclamped normalized RGB.
+    fn ypbpr_to_rgb(self, y: f32, pb: f32, pr: f32) -> [f32; 3] {
+        let (kr, kb) = self.coefficients();
+        let kg = 1.0 - kr - kb;
+
+        let r = 2.0f32.mul_add(pr * (1.0 - kr), y);
+        let b = 2.0f32.mul_add(pb * (1.0 - kb), y);
+        let g = (kb.mul_add(-b, kr.mul_add(-r, y))) / kg;
+
+        [r, g, b]
+    }
+
+    /// Converts an 8-bit RGB triplet into an 8-bit YCbCr triplet.
+    pub fn rgb_to_ycbcr(self, rgb: [u8; 3]) -> [u8; 3] {
+        let (kr, kb) = self.coefficients();
+        let kg = 1.0 - kr - kb;
+
+        let [r, g, b] = rgb.map(|v| v as f32 / 255.0);
+        let y = kb.mul_add(b, kr.mul_add(r, kg * g));
+        let pb = (b - y) / (2.0 * (1.0 - kb));
+        let pr = (r - y) / (2.0 * (1.0 - kr));
+
+        [
+            to_u8(219.0f32.mul_add(y, 16.0)),
+            to_u8(224.0f32.mul_add(pb, 128.0)),
+            to_u8(224.0f32.mul_add(pr, 128.0)),
+        ]
+    }
+}
+
+#[inline]
+fn to_u8(v: f32) -> u8 {
+    v.round().clamp(0.0, 255.0) as u8
+}
+
+/// Converts a normalized sRGB-encoded value into linear light.
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+impl<T: Pixel> Frame<T> {
+    /// Returns the mean relative luminance of the frame in linear light, in
+    /// the range `0.0..=1.0`.
+    ///
+    /// Every pixel is converted to RGB with `matrix`, upsampling chroma with
+    /// nearest-neighbor sampling, and linearized with the sRGB transfer
+    /// function. Monochrome frames use neutral chroma. Empty frames return
+    /// NaN.
+    ///
+    /// See [`Plane::average`] for a cheaper
+    /// estimate that averages the raw codewords.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is less than 8 or larger than the size of `T` in bits
+    pub fn average_luminance(&self, bit_depth: usize, matrix: ColorMatrix) -> f64 {
+        let (kr, kb) = matrix.coefficients();
+        let kg = 1.0 - kr - kb;
+        let ypbpr = self.ypbpr_sampler(bit_depth);
+        let luma = &self.planes[0];
+
+        let mut sum = 0f64;
+        for y in 0..luma.cfg.height {
+            for x in 0..luma.cfg.width {
+                let [y, pb, pr] = ypbpr(x, y);
+                let [r, g, b] = matrix
+                    .ypbpr_to_rgb(y, pb, pr)
+                    .map(|v| srgb_to_linear(v.clamp(0.0, 1.0)));
+                sum += kb.mul_add(b, kr.mul_add(r, kg * g)) as f64;
+            }
+        }
+
+        sum / (luma.cfg.width * luma.cfg.height) as f64
+    }
+
+    /// Writes the visible area of the frame as a binary (P6) PPM image, for
+    /// quick visual inspection.
+    ///
+    /// Every pixel is converted to 8-bit RGB with `matrix`, upsampling chroma
+    /// with nearest-neighbor sampling. Monochrome frames produce gray images.
+    ///
+    /// # Errors
+    ///
+    /// - If writing to `writer` fails
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is less than 8 or larger than the size of `T` in bits
+    #[cfg(feature = "std")]
+    pub fn write_ppm<W: Write>(
+        &self,
+        writer: &mut W,
+        matrix: ColorMatrix,
+        bit_depth: usize,
+    ) -> io::Result<()> {
+        let ypbpr = self.ypbpr_sampler(bit_depth);
+        let luma = &self.planes[0];
+
+        write!(writer, "P6\n{} {}\n255\n", luma.cfg.width, luma.cfg.height)?;
+        let mut buf = Vec::with_capacity(luma.cfg.width * 3);
+        for y in 0..luma.cfg.height {
+            buf.clear();
+            for x in 0..luma.cfg.width {
+                let [y, pb, pr] = ypbpr(x, y);
+                let rgb = matrix.ypbpr_to_rgb(y, pb, pr).map(|v| to_u8(v * 255.0));
+                buf.extend_from_slice(&rgb);
+            }
+            writer.write_all(&buf)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns a function mapping the visible position `(x, y)` to the
+    /// normalized luma and color differences of that pixel.
+    ///
+    /// Chroma is upsampled with nearest-neighbor sampling, and monochrome
+    /// frames use neutral chroma.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is less than 8 or larger than the size of `T` in bits
+    fn ypbpr_sampler(&self, bit_depth: usize) -> impl Fn(usize, usize) -> [f32; 3] + '_ {
+        assert!(
+            (8..=T::BITS as usize).contains(&bit_depth),
+            "invalid bit depth ({}) for a {}-bit pixel",
+            bit_depth,
+            T::BITS
+        );
+        let scale = (1u32 << (bit_depth - 8)) as f32;
+        let mono = self.chroma_sampling() == ChromaSampling::Cs400;
+        let [luma, cb, cr] = &self.planes;
+        let (xdec, ydec) = (cb.cfg.xdec, cb.cfg.ydec);
+
+        move |x, y| {
+            let chroma = |plane: &Plane<T>| {
+                if mono {
+                    0.0
+                } else {
+                    (plane.p(x >> xdec, y >> ydec).to_f32() / scale - 128.0) / 224.0
+                }
+            };
+            let luma = (luma.p(x, y).to_f32() / scale - 16.0) / 219.0;
+            [luma, chroma(cb), chroma(cr)]
+        }
+    }
+}
+
+impl Frame<u8> {
+    /// Converts packed 8-bit RGB data, three bytes per pixel without row
+    /// padding, into a new unpadded frame.
+    ///
+    /// Subsampled chroma is computed by averaging the chroma of every pixel
+    /// covered by a chroma sample.
+    ///
+    /// # Panics
+    ///
+    /// - If the length of `data` is not `width * height * 3`
+    pub fn from_rgb24(
+        data: &[u8],
+        width: usize,
+        height: usize,
+        matrix: ColorMatrix,
+        chroma_sampling: ChromaSampling,
+    ) -> Self {
+        assert_eq!(
+            data.len(),
+            width * height * 3,
+            "RGB24 data does not match the dimensions {}x{}",
+            width,
+            height
+        );
+        let mut frame = Frame::new_unaligned(width, height, chroma_sampling);
+
+        let ycbcr: Vec<[u8; 3]> = data
+            .chunks_exact(3)
+            .map(|rgb| matrix.rgb_to_ycbcr([rgb[0], rgb[1], rgb[2]]))
+            .collect();
+
+        for (y, row) in frame.planes[0].rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = ycbcr[y * width + x][0];
+            }
+        }
+
+        for (pli, plane) in frame.planes.iter_mut().enumerate().skip(1) {
+            let xdec = plane.cfg.xdec;
+            let ydec = plane.cfg.ydec;
+            for (cy, row) in plane.rows_iter_mut().enumerate() {
+                for (cx, pixel) in row.iter_mut().enumerate() {
+                    let mut sum = 0u32;
+                    let mut count = 0u32;
+                    for y in (cy << ydec)..((cy + 1) << ydec).min(height) {
+                        for x in (cx << xdec)..((cx + 1) << xdec).min(width) {
+                            sum += ycbcr[y * width + x][pli] as u32;
+                            count += 1;
+                        }
+                    }
+                    *pixel = ((sum + count / 2) / count) as u8;
+                }
+            }
+        }
+
+        frame
+    }
+}
+
+#[cfg(feature = "image")]
+mod image_impl {
+    use super::ColorMatrix;
+    use crate::frame::Frame;
+    use crate::pixel::ChromaSampling;
+    use image::{Rgb, RgbImage};
+
+    impl Frame<u8> {
+        /// Converts the visible area of the frame into an RGB image.
+        ///
+        /// Chroma is upsampled with nearest-neighbor sampling according to the
+        /// chroma sampling of the frame. Monochrome frames produce gray images.
+        pub fn to_rgb_image(&self, matrix: ColorMatrix) -> RgbImage {
+            let luma = &self.planes[0];
+            let mono = self.chroma_sampling() == ChromaSampling::Cs400;
+            let xdec = self.planes[1].cfg.xdec;
+            let ydec = self.planes[1].cfg.ydec;
+
+            RgbImage::from_fn(luma.cfg.width as u32, luma.cfg.height as u32, |x, y| {
+                let (x, y) = (x as usize, y as usize);
+                let (cb, cr) = if mono {
+                    (128, 128)
+                } else {
+                    (
+                        self.planes[1].p(x >> xdec, y >> ydec),
+                        self.planes[2].p(x >> xdec, y >> ydec),
+                    )
+                };
+                Rgb(matrix.ycbcr_to_rgb(luma.p(x, y), cb, cr))
+            })
+        }
+
+        /// Converts an RGB image into a new unpadded frame, see
+        /// [`Frame::from_rgb24`].
+        pub fn from_rgb_image(
+            image: &RgbImage,
+            chroma_sampling: ChromaSampling,
+            matrix: ColorMatrix,
+        ) -> Self {
+            Frame::from_rgb24(
+                image.as_raw(),
+                image.width() as usize,
+                image.height() as usize,
+                matrix,
+                chroma_sampling,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn rgb_image_round_trip() {
+            let image = RgbImage::from_fn(4, 4, |x, y| Rgb([x as u8 * 60, y as u8 * 60, 128]));
+
+            for chroma_sampling in [ChromaSampling::Cs444, ChromaSampling::Cs420] {
+                let frame = Frame::from_rgb_image(&image, chroma_sampling, ColorMatrix::Bt709);
+                assert_eq!(frame.chroma_sampling(), chroma_sampling);
+
+                let rgb = frame.to_rgb_image(ColorMatrix::Bt709);
+                assert_eq!(rgb.dimensions(), (4, 4));
+                if chroma_sampling == ChromaSampling::Cs444 {
+                    for (a, b) in rgb.pixels().zip(image.pixels()) {
+                        for c in 0..3 {
+                            assert!((a.0[c] as i32 - b.0[c] as i32).abs() <= 2);
+                        }
+                    }
+                }
+            }
+
+            // 4:2:0 round trips closely when every 2x2 block has a single color
+            let colors = [[200, 30, 60], [20, 180, 90], [70, 70, 220], [128, 128, 128]];
+            let image = RgbImage::from_fn(4, 4, |x, y| Rgb(colors[(y / 2 * 2 + x / 2) as usize]));
+            let frame = Frame::from_rgb_image(&image, ChromaSampling::Cs420, ColorMatrix::Bt709);
+            let rgb = frame.to_rgb_image(ColorMatrix::Bt709);
+            for (a, b) in rgb.pixels().zip(image.pixels()) {
+                for c in 0..3 {
+                    assert!((a.0[c] as i32 - b.0[c] as i32).abs() <= 2);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn primaries() {
+        for matrix in [ColorMatrix::Bt601, ColorMatrix::Bt709] {
+            assert_eq!(matrix.ycbcr_to_rgb(16, 128, 128), [0, 0, 0]);
+            assert_eq!(matrix.ycbcr_to_rgb(235, 128, 128), [255, 255, 255]);
+            assert_eq!(matrix.rgb_to_ycbcr([255, 255, 255]), [235, 128, 128]);
+            assert_eq!(matrix.rgb_to_ycbcr([0, 0, 0]), [16, 128, 128]);
+        }
+        assert_eq!(ColorMatrix::Bt601.rgb_to_ycbcr([255, 0, 0]), [81, 90, 240]);
+        assert_eq!(ColorMatrix::Bt709.rgb_to_ycbcr([255, 0, 0]), [63, 102, 240]);
+    }
+
+    #[test]
+    fn from_rgb24() {
+        // a red and a blue column
+        let data: Vec<u8> = (0..4).flat_map(|_| [255, 0, 0, 0, 0, 255]).collect();
+
+        let frame = Frame::from_rgb24(&data, 2, 4, ColorMatrix::Bt601, ChromaSampling::Cs444);
+        assert_eq!(frame.planes[0].rows_iter().next().unwrap(), [81, 41]);
+        assert_eq!(frame.planes[1].rows_iter().next().unwrap(), [90, 240]);
+        assert_eq!(frame.planes[2].rows_iter().next().unwrap(), [240, 110]);
+
+        let frame = Frame::from_rgb24(&data, 2, 4, ColorMatrix::Bt601, ChromaSampling::Cs420);
+        assert_eq!(frame.planes[1].cfg.width, 1);
+        assert_eq!(frame.planes[1].cfg.height, 2);
+        assert!(frame.planes[1].iter().all(|p| p == 165));
+        assert!(frame.planes[2].iter().all(|p| p == 175));
+
+        let frame = Frame::from_rgb24(&data, 2, 4, ColorMatrix::Bt601, ChromaSampling::Cs400);
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs400);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the dimensions")]
+    fn from_rgb24_length() {
+        Frame::from_rgb24(&[0; 11], 2, 2, ColorMatrix::Bt709, ChromaSampling::Cs420);
+    }
+
+    #[test]
+    fn write_ppm() {
+        let mut frame = Frame::<u16>::new_unaligned(8, 2, ChromaSampling::Cs422);
+        frame.planes[0].fill(235 << 2);
+        frame.planes[1].fill(128 << 2);
+        frame.planes[2].fill(128 << 2);
+        frame.planes[0].set(1, 1, 16 << 2);
+
+        let mut out = Vec::new();
+        frame.write_ppm(&mut out, ColorMatrix::Bt709, 10).unwrap();
+        let header = b"P6\n8 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 8 * 2 * 3);
+        assert_eq!(pixels[..3], [255, 255, 255]);
+        assert_eq!(pixels[(8 + 1) * 3..][..3], [0, 0, 0]);
+
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        frame.planes[0].fill(235);
+        let mut out = Vec::new();
+        frame.write_ppm(&mut out, ColorMatrix::Bt601, 8).unwrap();
+        assert!(out[b"P6\n8 8\n255\n".len()..].iter().all(|&v| v == 255));
+    }
+
+    #[test]
+    fn average_luminance() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.planes[0].fill(16 << 2);
+        frame.planes[1].fill(128 << 2);
+        frame.planes[2].fill(128 << 2);
+        assert_eq!(frame.average_luminance(10, ColorMatrix::Bt709), 0.0);
+
+        frame.planes[0].fill(235 << 2);
+        let white = frame.average_luminance(10, ColorMatrix::Bt709);
+        assert!((white - 1.0).abs() < 1e-6);
+
+        // mid-gray codewords are much darker in linear light
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        frame.planes[0].fill(126);
+        let gray = frame.average_luminance(8, ColorMatrix::Bt601);
+        assert!((gray - 0.216).abs() < 1e-3);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid bit depth")]
+    fn write_ppm_bit_depth_too_large() {
+        let frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        frame
+            .write_ppm(&mut Vec::new(), ColorMatrix::Bt709, 10)
+            .ok();
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid bit depth")]
+    fn average_luminance_low_bit_depth() {
+        Frame::<u8>::new(8, 8, ChromaSampling::Cs420).average_luminance(6, ColorMatrix::Bt709);
+    }
+}
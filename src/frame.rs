@@ -12,53 +12,4535 @@ use crate::pixel::*;
 use crate::plane::*;
 use crate::serialize::{Deserialize, Serialize};
 
-// One video frame.
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+#[cfg(feature = "std")]
+use num_traits::FromPrimitive;
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+/// Errors returned by frame operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// The planes of two frames do not have the same dimensions, or the planes
+    /// of a frame have inconsistent dimensions.
+    DimensionMismatch,
+    /// The allocation size of the frame overflows `usize` or exceeds the
+    /// requested limit.
+    TooLarge,
+    /// The allocation of the frame data failed.
+    AllocationFailed,
+    /// The serialized data is malformed or truncated.
+    InvalidData,
+    /// The serialized data does not start with the expected magic number, or
+    /// uses an unsupported version of the format.
+    UnsupportedFormat,
+    /// The serialized data ends before the end of the frame.
+    Truncated,
+    /// A sample value does not fit in the target pixel type.
+    SampleOutOfRange,
+    /// The dimensions are not a multiple of the chroma subsampling.
+    InvalidDimensions {
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+    },
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrameError::DimensionMismatch => write!(f, "frame dimensions do not match"),
+            FrameError::TooLarge => write!(f, "frame is too large"),
+            FrameError::AllocationFailed => write!(f, "frame allocation failed"),
+            FrameError::InvalidData => write!(f, "invalid serialized frame data"),
+            FrameError::UnsupportedFormat => write!(f, "unsupported serialized frame format"),
+            FrameError::Truncated => write!(f, "serialized frame data is truncated"),
+            FrameError::SampleOutOfRange => write!(f, "sample value out of range"),
+            FrameError::InvalidDimensions {
+                width,
+                height,
+                chroma_sampling,
+            } => write!(
+                f,
+                "dimensions {}x{} are not compatible with {} chroma sampling",
+                width, height, chroma_sampling
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+/// Order of the chroma planes in planar and semi-planar data.
+///
+/// Frames always store the planes in Y, U, V order; the order only applies
+/// when reading or writing raw data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaneOrder {
+    /// U (Cb) before V (Cr), as in I420 and NV12.
+    #[default]
+    Yuv,
+    /// V (Cr) before U (Cb), as in YV12 and NV21.
+    Yvu,
+}
+
+impl PlaneOrder {
+    /// Returns the indices of the planes in the order they are stored.
+    const fn plane_indices(self) -> [usize; 3] {
+        match self {
+            PlaneOrder::Yuv => [0, 1, 2],
+            PlaneOrder::Yvu => [0, 2, 1],
+        }
+    }
+}
+
+/// Clockwise rotation applied by [`Frame::rotate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// Rotate by 90 degrees.
+    R90,
+    /// Rotate by 180 degrees.
+    R180,
+    /// Rotate by 270 degrees.
+    R270,
+}
+
+impl<T: Pixel> Hash for Frame<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_visible(state);
+    }
+}
+
+/// Deserializes a new unpadded frame from the format written by
+/// [`Frame::to_tagged_bytes`].
+///
+/// The header is validated before anything is allocated.
+///
+/// # Errors
+///
+/// - [`FrameError::UnsupportedFormat`] if the magic number or the version
+///   does not match
+/// - [`FrameError::Truncated`] if the data ends before the end of the header
+///   or of the planes
+/// - [`FrameError::InvalidData`] if the header is malformed, the pixel size
+///   does not match `T`, or there is data after the planes
+#[cfg(feature = "std")]
+impl<T: Pixel> TryFrom<&[u8]> for Frame<T> {
+    type Error = FrameError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() < 7 {
+            return Err(FrameError::Truncated);
+        }
+        let (magic, rest) = data.split_at(4);
+        if magic != Self::TAGGED_MAGIC || rest[0] != Self::TAGGED_VERSION {
+            return Err(FrameError::UnsupportedFormat);
+        }
+        let fields_len = u16::from_le_bytes([rest[1], rest[2]]) as usize;
+        if fields_len < Self::TAGGED_FIELDS_LEN as usize {
+            return Err(FrameError::InvalidData);
+        }
+        let fields = rest[3..].get(..fields_len).ok_or(FrameError::Truncated)?;
+        let planes = &rest[3 + fields_len..];
+
+        let width = u32::from_le_bytes([fields[0], fields[1], fields[2], fields[3]]) as usize;
+        let height = u32::from_le_bytes([fields[4], fields[5], fields[6], fields[7]]) as usize;
+        let chroma_sampling: ChromaSampling =
+            FromPrimitive::from_u8(fields[8]).ok_or(FrameError::InvalidData)?;
+        if fields[9] as usize != mem::size_of::<T>() {
+            return Err(FrameError::InvalidData);
+        }
+
+        let expected_len =
+            Self::planar_len(width, height, chroma_sampling).ok_or(FrameError::InvalidData)?;
+        if planes.len() < expected_len {
+            return Err(FrameError::Truncated);
+        }
+        if planes.len() > expected_len {
+            return Err(FrameError::InvalidData);
+        }
+
+        Self::read_planar(
+            &mut &planes[..],
+            width,
+            height,
+            chroma_sampling,
+            T::BITS as usize,
+            Endianness::Little,
+        )
+        .map_err(|_| FrameError::InvalidData)
+    }
+}
+
+/// A sample that differs between two frames, found by
+/// [`Frame::approx_eq_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleMismatch<T: Pixel> {
+    /// Index of the plane.
+    pub plane: usize,
+    /// Visible horizontal position in the plane.
+    pub x: usize,
+    /// Visible vertical position in the plane.
+    pub y: usize,
+    /// Value of the sample in the compared frame.
+    pub left: T,
+    /// Value of the sample in the other frame.
+    pub right: T,
+}
+
+/// Memory used by the planes of a frame, returned by [`Frame::memory_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameMemory {
+    /// Size of the allocated data in bytes, including padding and stride
+    /// alignment.
+    pub allocated_bytes: usize,
+    /// Size of the visible pixels in bytes.
+    pub visible_bytes: usize,
+}
+
+impl FrameMemory {
+    /// Returns the memory spent on padding and alignment, relative to the
+    /// visible size, so that 0.0 means no overhead.
+    ///
+    /// Returns NaN for empty frames.
+    pub fn padding_overhead(&self) -> f64 {
+        (self.allocated_bytes - self.visible_bytes) as f64 / self.visible_bytes as f64
+    }
+}
+
+/// Synthetic content generated by [`Frame::test_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestPattern<T: Pixel> {
+    /// Uniform luma with the given value and neutral chroma.
+    SolidGray(T),
+    /// Black and white squares of `size` x `size` luma pixels, starting with
+    /// black in the top left corner.
+    Checkerboard {
+        /// Side of the squares, in luma pixels.
+        size: usize,
+    },
+    /// Luma rising from 0 in the left column to the largest value of the bit
+    /// depth in the right column, with neutral chroma.
+    HorizontalRamp,
+    /// The seven 75% SMPTE color bars: white, yellow, cyan, green, magenta,
+    /// red and blue, in BT.601 limited range.
+    ColorBars,
+}
+
+/// 8-bit BT.601 Y, Cb, Cr values of the 75% SMPTE color bars.
+const SMPTE_BARS: [[u8; 3]; 7] = [
+    [180, 128, 128],
+    [162, 44, 142],
+    [131, 156, 44],
+    [112, 72, 58],
+    [84, 184, 198],
+    [65, 100, 212],
+    [35, 212, 114],
+];
+
+/// One video frame.
+///
+/// `==` is a structural comparison, which also compares the stride and the
+/// padding of the planes. Use [`Frame::visible_eq`] to only compare the
+/// visible pixels.
+///
+/// [`Hash`] only covers the chroma sampling, the plane dimensions and the
+/// visible pixels, like [`Frame::content_hash`]. Frames that are equal hash
+/// equally, and so do frames that only differ in their stride or padding.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Frame<T: Pixel> {
-    /// Planes constituting the frame.
+    /// Planes constituting the frame, in Y, U (Cb), V (Cr) order.
     pub planes: [Plane<T>; 3],
 }
 
 impl<T: Pixel> Frame<T> {
+    /// Returns the largest sample value representable with `bit_depth` bits.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is 0 or larger than the size of `T` in bits
+    pub fn max_value(bit_depth: usize) -> T {
+        assert!(
+            bit_depth > 0 && bit_depth <= T::BITS as usize,
+            "invalid bit depth ({}) for a {}-bit pixel",
+            bit_depth,
+            T::BITS
+        );
+        T::cast_from((1u32 << bit_depth) - 1)
+    }
+
+    /// Creates a new frame with the given parameters and no padding.
+    ///
+    /// Allocates data for the planes.
+    pub fn new(width: usize, height: usize, chroma_sampling: ChromaSampling) -> Self {
+        Self::new_with_padding(width, height, chroma_sampling, 0)
+    }
+
     /// Creates a new frame with the given parameters.
     ///
     /// Allocates data for the planes.
+    ///
+    /// The dimensions are rounded up to a multiple of 8. Unlike
+    /// [`Frame::try_new_with_padding`], they are not validated against the
+    /// chroma subsampling in release builds, and debug builds only assert
+    /// that they are a multiple of it.
+    ///
+    /// The chroma padding is `luma_padding` divided by the chroma subsampling,
+    /// rounded up, so the chroma borders always cover at least the luma
+    /// borders, even when `luma_padding` is not a multiple of the
+    /// subsampling.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame is too large or the allocation fails
+    /// - In debug builds, if `width` or `height` is not a multiple of the
+    ///   chroma subsampling
     pub fn new_with_padding(
         width: usize,
         height: usize,
         chroma_sampling: ChromaSampling,
         luma_padding: usize,
     ) -> Self {
-        let luma_width = width.align_power_of_two(3);
-        let luma_height = height.align_power_of_two(3);
+        debug_assert!(
+            Self::dimensions_fit(width, height, chroma_sampling),
+            "{}",
+            FrameError::InvalidDimensions {
+                width,
+                height,
+                chroma_sampling
+            }
+        );
+        Self::alloc_with_padding(width, height, chroma_sampling, luma_padding, None, false)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a new frame with the given parameters, without panicking or
+    /// aborting if the frame cannot be allocated.
+    ///
+    /// Allocates data for the planes, as long as their total size in bytes
+    /// does not exceed `max_bytes`.
+    ///
+    /// Unlike [`Frame::new_with_padding`], the dimensions are always
+    /// validated against the chroma subsampling.
+    ///
+    /// # Errors
+    ///
+    /// - [`FrameError::InvalidDimensions`] if `width` or `height` is not a
+    ///   multiple of the chroma subsampling, e.g. an odd width for 4:2:0
+    /// - [`FrameError::TooLarge`] if the size of the frame overflows `usize`
+    ///   or exceeds `max_bytes`
+    /// - [`FrameError::AllocationFailed`] if the allocation fails
+    pub fn try_new_with_padding(
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        luma_padding: usize,
+        max_bytes: Option<usize>,
+    ) -> Result<Self, FrameError> {
+        Self::alloc_with_padding(
+            width,
+            height,
+            chroma_sampling,
+            luma_padding,
+            max_bytes,
+            true,
+        )
+    }
+
+    /// Returns whether `width` and `height` are a multiple of the chroma
+    /// subsampling.
+    fn dimensions_fit(width: usize, height: usize, chroma_sampling: ChromaSampling) -> bool {
+        let (xdec, ydec) = chroma_sampling.get_decimation().unwrap_or((0, 0));
+        width & ((1 << xdec) - 1) == 0 && height & ((1 << ydec) - 1) == 0
+    }
+
+    fn alloc_with_padding(
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        luma_padding: usize,
+        max_bytes: Option<usize>,
+        validate_dimensions: bool,
+    ) -> Result<Self, FrameError> {
+        if validate_dimensions && !Self::dimensions_fit(width, height, chroma_sampling) {
+            return Err(FrameError::InvalidDimensions {
+                width,
+                height,
+                chroma_sampling,
+            });
+        }
+
+        let cfgs = Self::plane_configs(width, height, chroma_sampling, luma_padding)?;
+
+        let mut total_bytes = 0usize;
+        for cfg in &cfgs {
+            total_bytes = (cfg.stride * cfg.alloc_height * mem::size_of::<T>())
+                .checked_add(total_bytes)
+                .ok_or(FrameError::TooLarge)?;
+        }
+        if max_bytes.is_some_and(|max_bytes| total_bytes > max_bytes) {
+            return Err(FrameError::TooLarge);
+        }
+
+        let new_plane = |cfg: &PlaneConfig| {
+            Plane::try_new(
+                cfg.width, cfg.height, cfg.xdec, cfg.ydec, cfg.xpad, cfg.ypad,
+            )
+            .ok_or(FrameError::AllocationFailed)
+        };
+
+        Ok(Frame {
+            planes: [
+                new_plane(&cfgs[0])?,
+                new_plane(&cfgs[1])?,
+                new_plane(&cfgs[2])?,
+            ],
+        })
+    }
+
+    /// Returns the configurations of the planes allocated by
+    /// [`Frame::new_with_padding`] for the given parameters.
+    pub(crate) fn plane_configs(
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        luma_padding: usize,
+    ) -> Result<[PlaneConfig; 3], FrameError> {
+        if width.max(height).align_power_of_two_checked(3).is_none() {
+            return Err(FrameError::TooLarge);
+        }
+        let (luma_width, luma_height) = chroma_sampling.round_dimensions_up(width, height);
 
         let (chroma_decimation_x, chroma_decimation_y) =
             chroma_sampling.get_decimation().unwrap_or((0, 0));
         let (chroma_width, chroma_height) =
             chroma_sampling.get_chroma_dimensions(luma_width, luma_height);
-        let chroma_padding_x = luma_padding >> chroma_decimation_x;
-        let chroma_padding_y = luma_padding >> chroma_decimation_y;
+        // Round up, so that the chroma borders cover the luma borders
+        let chroma_padding = |dec: usize| {
+            luma_padding
+                .checked_add((1 << dec) - 1)
+                .map(|padding| padding >> dec)
+                .ok_or(FrameError::TooLarge)
+        };
+        let chroma_padding_x = chroma_padding(chroma_decimation_x)?;
+        let chroma_padding_y = chroma_padding(chroma_decimation_y)?;
+
+        let luma_cfg = (luma_width, luma_height, 0, 0, luma_padding, luma_padding);
+        let chroma_cfg = (
+            chroma_width,
+            chroma_height,
+            chroma_decimation_x,
+            chroma_decimation_y,
+            chroma_padding_x,
+            chroma_padding_y,
+        );
+
+        let new_cfg = |(width, height, xdec, ydec, xpad, ypad)| {
+            PlaneConfig::try_new(width, height, xdec, ydec, xpad, ypad, mem::size_of::<T>())
+                .ok_or(FrameError::TooLarge)
+        };
+
+        Ok([
+            new_cfg(luma_cfg)?,
+            new_cfg(chroma_cfg)?,
+            new_cfg(chroma_cfg)?,
+        ])
+    }
+
+    /// Creates a new unpadded frame whose visible luma dimensions are exactly
+    /// `width` and `height`.
+    pub(crate) fn new_unaligned(
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+    ) -> Self {
+        let (xdec, ydec) = chroma_sampling.get_decimation().unwrap_or((0, 0));
+        let (chroma_width, chroma_height) = chroma_sampling.get_chroma_dimensions(width, height);
+
+        Frame {
+            planes: [
+                Plane::new(width, height, 0, 0, 0, 0),
+                Plane::new(chroma_width, chroma_height, xdec, ydec, 0, 0),
+                Plane::new(chroma_width, chroma_height, xdec, ydec, 0, 0),
+            ],
+        }
+    }
+
+    /// Assembles a frame from separately created planes.
+    ///
+    /// # Panics
+    ///
+    /// - If the planes are not consistent with `chroma_sampling`, see
+    ///   [`Frame::try_from_planes`]
+    pub fn from_planes(planes: [Plane<T>; 3], chroma_sampling: ChromaSampling) -> Self {
+        match Self::try_from_planes(planes, chroma_sampling) {
+            Ok(frame) => frame,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Assembles a frame from separately created planes.
+    ///
+    /// # Errors
+    ///
+    /// - [`FrameError::DimensionMismatch`] if the luma plane is decimated, or
+    ///   if the decimation or the visible dimensions of the chroma planes do
+    ///   not match the ones derived from the luma plane and `chroma_sampling`
+    pub fn try_from_planes(
+        planes: [Plane<T>; 3],
+        chroma_sampling: ChromaSampling,
+    ) -> Result<Self, FrameError> {
+        let luma = &planes[0].cfg;
+        let (chroma_width, chroma_height) =
+            chroma_sampling.get_chroma_dimensions(luma.width, luma.height);
+        let chroma_ok = |cfg: &PlaneConfig| match chroma_sampling.get_decimation() {
+            Some((xdec, ydec)) => {
+                cfg.xdec == xdec
+                    && cfg.ydec == ydec
+                    && cfg.width == chroma_width
+                    && cfg.height == chroma_height
+            }
+            None => cfg.width == 0 || cfg.height == 0,
+        };
+
+        if luma.xdec != 0
+            || luma.ydec != 0
+            || !chroma_ok(&planes[1].cfg)
+            || !chroma_ok(&planes[2].cfg)
+        {
+            return Err(FrameError::DimensionMismatch);
+        }
+
+        Ok(Frame { planes })
+    }
+
+    /// Creates a frame with no pixels, for use as a placeholder until the
+    /// real dimensions are known.
+    ///
+    /// The chroma planes carry the decimation of `chroma_sampling`. Since an
+    /// empty monochrome frame cannot be told apart from an empty 4:4:4 frame,
+    /// [`Frame::chroma_sampling`] reports `Cs444` for the former.
+    pub fn empty(chroma_sampling: ChromaSampling) -> Self {
+        Frame::new_unaligned(0, 0, chroma_sampling)
+    }
+
+    /// Returns the luma (Y) plane.
+    pub fn luma(&self) -> &Plane<T> {
+        &self.planes[0]
+    }
+
+    /// Returns the luma (Y) plane mutably.
+    pub fn luma_mut(&mut self) -> &mut Plane<T> {
+        &mut self.planes[0]
+    }
+
+    /// Returns the U (Cb) and V (Cr) planes, or `None` for monochrome frames.
+    pub fn chroma(&self) -> Option<(&Plane<T>, &Plane<T>)> {
+        if self.chroma_sampling() == ChromaSampling::Cs400 {
+            return None;
+        }
+        let [_, cb, cr] = &self.planes;
+        Some((cb, cr))
+    }
+
+    /// Returns the U (Cb) and V (Cr) planes mutably, or `None` for monochrome
+    /// frames.
+    pub fn chroma_mut(&mut self) -> Option<(&mut Plane<T>, &mut Plane<T>)> {
+        if self.chroma_sampling() == ChromaSampling::Cs400 {
+            return None;
+        }
+        let [_, cb, cr] = &mut self.planes;
+        Some((cb, cr))
+    }
+
+    /// Iterates over the non-empty planes along with their index and their
+    /// `(xdec, ydec)` decimation, so that monochrome frames only yield the
+    /// luma plane.
+    pub fn iter_planes(&self) -> impl Iterator<Item = (usize, (usize, usize), &Plane<T>)> {
+        self.planes
+            .iter()
+            .enumerate()
+            .filter(|(_, plane)| plane.cfg.width > 0 && plane.cfg.height > 0)
+            .map(|(pli, plane)| (pli, (plane.cfg.xdec, plane.cfg.ydec), plane))
+    }
+
+    /// Iterates mutably over the non-empty planes along with their index and
+    /// their decimation.
+    ///
+    /// See [`Frame::iter_planes`].
+    pub fn iter_planes_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (usize, (usize, usize), &mut Plane<T>)> {
+        self.planes
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, plane)| plane.cfg.width > 0 && plane.cfg.height > 0)
+            .map(|(pli, plane)| (pli, (plane.cfg.xdec, plane.cfg.ydec), plane))
+    }
+
+    /// Returns the chroma sampling of the frame, as derived from the
+    /// decimation of its chroma planes.
+    pub fn chroma_sampling(&self) -> ChromaSampling {
+        let luma = &self.planes[0].cfg;
+        let chroma = &self.planes[1].cfg;
+        if luma.width > 0 && luma.height > 0 && (chroma.width == 0 || chroma.height == 0) {
+            return ChromaSampling::Cs400;
+        }
+        match (chroma.xdec, chroma.ydec) {
+            (1, 1) => ChromaSampling::Cs420,
+            (1, 0) => ChromaSampling::Cs422,
+            (0, 1) => ChromaSampling::Cs440,
+            (2, 0) => ChromaSampling::Cs411,
+            _ => ChromaSampling::Cs444,
+        }
+    }
+
+    /// Returns a new unpadded frame containing a copy of the given luma
+    /// rectangle.
+    ///
+    /// The chroma rectangle is derived from the chroma decimation of the frame,
+    /// with the origin rounded down and the dimensions rounded up.
+    ///
+    /// # Panics
+    ///
+    /// - If the rectangle exceeds the visible dimensions of the frame
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Frame<T> {
+        let luma = &self.planes[0].cfg;
+        assert!(
+            x + width <= luma.width && y + height <= luma.height,
+            "crop rectangle {}x{} at ({}, {}) exceeds the frame dimensions {}x{}",
+            width,
+            height,
+            x,
+            y,
+            luma.width,
+            luma.height
+        );
+
+        let mut cropped = Frame::new_unaligned(width, height, self.chroma_sampling());
+
+        for (dst, src) in cropped.planes.iter_mut().zip(self.planes.iter()) {
+            let xdec = src.cfg.xdec;
+            let ydec = src.cfg.ydec;
+            let src_x = (x >> xdec) as isize;
+            let src_y = y >> ydec;
+            let width = dst.cfg.width;
+
+            for (row_idx, dst_row) in dst.rows_iter_mut().enumerate() {
+                let range = src.row_range_cropped(src_x, (src_y + row_idx) as isize);
+                dst_row.copy_from_slice(&src.data[range][..width]);
+            }
+        }
+
+        cropped
+    }
+
+    /// Creates a new unpadded frame filled with a synthetic pattern.
+    ///
+    /// 8-bit levels, such as the limited range black and white of the
+    /// checkerboard, are scaled to `bit_depth`. Chroma samples take the value
+    /// of the pattern at their top left luma pixel.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is less than 8 or larger than the size of `T` in bits
+    /// - If the checkerboard size is 0
+    pub fn test_pattern(
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        bit_depth: usize,
+        pattern: TestPattern<T>,
+    ) -> Self {
+        let max = u32::cast_from(Self::max_value(bit_depth));
+        assert!(bit_depth >= 8, "invalid bit depth ({})", bit_depth);
+        if let TestPattern::Checkerboard { size } = pattern {
+            assert!(size > 0, "checkerboard squares must not be empty");
+        }
+        let scale = |v: u8| T::cast_from((v as u32) << (bit_depth - 8));
+        let neutral = T::cast_from(1u32 << (bit_depth - 1));
+
+        let mut frame = Frame::new_unaligned(width, height, chroma_sampling);
+        for (pli, plane) in frame.planes.iter_mut().enumerate() {
+            let (xdec, ydec) = (plane.cfg.xdec, plane.cfg.ydec);
+            for (y, row) in plane.rows_iter_mut().enumerate() {
+                let y = y << ydec;
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let x = x << xdec;
+                    *pixel = match pattern {
+                        TestPattern::SolidGray(value) if pli == 0 => value,
+                        TestPattern::Checkerboard { size } if pli == 0 => {
+                            scale(if (x / size + y / size) % 2 == 0 {
+                                16
+                            } else {
+                                235
+                            })
+                        }
+                        TestPattern::HorizontalRamp if pli == 0 => {
+                            let ramp = (x as u64 * max as u64) / (width.max(2) - 1) as u64;
+                            T::cast_from(ramp as u32)
+                        }
+                        TestPattern::ColorBars => scale(SMPTE_BARS[x * 7 / width][pli]),
+                        _ => neutral,
+                    };
+                }
+            }
+        }
+
+        frame
+    }
+
+    /// Returns the allocated and visible memory of the planes, to estimate
+    /// the overhead of the padding, e.g. before calling
+    /// [`Frame::compact_clone`].
+    pub fn memory_usage(&self) -> FrameMemory {
+        FrameMemory {
+            allocated_bytes: self.planes.iter().map(Plane::allocated_bytes).sum(),
+            visible_bytes: self.planes.iter().map(Plane::visible_bytes).sum(),
+        }
+    }
+
+    /// Returns a copy of the visible area of the frame, without any padding.
+    ///
+    /// Unlike `clone`, the padding is not allocated, so the result has a luma
+    /// padding of 0 and its borders must be regenerated, e.g. by allocating a
+    /// padded frame and copying into it, before applying filters that read
+    /// outside the visible area. Rows are still allocated with the stride
+    /// alignment required by the planes.
+    pub fn compact_clone(&self) -> Frame<T> {
+        let compact = |plane: &Plane<T>| {
+            let PlaneConfig {
+                width,
+                height,
+                xdec,
+                ydec,
+                ..
+            } = plane.cfg;
+            let mut new = Plane::new(width, height, xdec, ydec, 0, 0);
+            new.copy_from(plane);
+            new
+        };
+
+        Frame {
+            planes: [
+                compact(&self.planes[0]),
+                compact(&self.planes[1]),
+                compact(&self.planes[2]),
+            ],
+        }
+    }
+
+    /// Splits the visible area into a grid of `cols` x `rows` new unpadded
+    /// frames, in row-major order.
+    ///
+    /// The tile boundaries are spread evenly and snapped down to multiples of
+    /// the chroma subsampling, so that every tile starts on a chroma sample;
+    /// the last column and row absorb the remainder.
+    ///
+    /// # Panics
+    ///
+    /// - If `cols` or `rows` is 0
+    /// - If the frame is too small for every tile to be at least one chroma
+    ///   sample wide and high
+    pub fn tiles(&self, cols: usize, rows: usize) -> Vec<Frame<T>> {
+        assert!(cols > 0 && rows > 0, "tile grid must not be empty");
+        let (xdec, ydec) = self.chroma_sampling().get_decimation().unwrap_or((0, 0));
+        let luma = &self.planes[0].cfg;
+
+        let boundaries = |len: usize, count: usize, dec: usize| -> Vec<usize> {
+            let mut bounds: Vec<usize> = (0..count)
+                .map(|i| (i * len / count) & !((1 << dec) - 1))
+                .collect();
+            bounds.push(len);
+            assert!(
+                bounds.windows(2).all(|w| w[0] < w[1]),
+                "frame dimension {} is too small for {} tiles",
+                len,
+                count
+            );
+            bounds
+        };
+        let xs = boundaries(luma.width, cols, xdec);
+        let ys = boundaries(luma.height, rows, ydec);
+
+        let mut tiles = Vec::with_capacity(cols * rows);
+        for y in ys.windows(2) {
+            for x in xs.windows(2) {
+                tiles.push(self.crop(x[0], y[0], x[1] - x[0], y[1] - y[0]));
+            }
+        }
+        tiles
+    }
+
+    /// Stitches a row-major grid of `cols` x `rows` tiles, such as the ones
+    /// returned by [`Frame::tiles`], into a new unpadded frame.
+    ///
+    /// # Panics
+    ///
+    /// - If the number of tiles is not `cols * rows`
+    /// - If a tile does not use `chroma_sampling`
+    /// - If the tiles of a row do not have the same height, or the tiles of a
+    ///   column do not have the same width
+    /// - If the tile dimensions do not add up to `full_width` x `full_height`
+    /// - If a tile does not start on a chroma sample
+    pub fn from_tiles(
+        tiles: &[Frame<T>],
+        cols: usize,
+        rows: usize,
+        full_width: usize,
+        full_height: usize,
+        chroma_sampling: ChromaSampling,
+    ) -> Frame<T> {
+        assert_eq!(
+            tiles.len(),
+            cols * rows,
+            "expected {} x {} tiles, got {}",
+            cols,
+            rows,
+            tiles.len()
+        );
+        let (xdec, ydec) = chroma_sampling.get_decimation().unwrap_or((0, 0));
+        let size = |tile: &Frame<T>| (tile.planes[0].cfg.width, tile.planes[0].cfg.height);
+
+        let mut frame = Frame::new_unaligned(full_width, full_height, chroma_sampling);
+        let mut y = 0;
+        for (row_idx, row) in tiles.chunks(cols.max(1)).enumerate() {
+            let height = size(&row[0]).1;
+            let mut x = 0;
+            for (col_idx, tile) in row.iter().enumerate() {
+                let (width, tile_height) = size(tile);
+                assert_eq!(
+                    tile.chroma_sampling(),
+                    chroma_sampling,
+                    "tile ({}, {}) does not use {} chroma sampling",
+                    col_idx,
+                    row_idx,
+                    chroma_sampling
+                );
+                assert_eq!(
+                    tile_height, height,
+                    "tile ({}, {}) is {} pixels high, expected {}",
+                    col_idx, row_idx, tile_height, height
+                );
+                let expected_width = size(&tiles[col_idx]).0;
+                assert_eq!(
+                    width, expected_width,
+                    "tile ({}, {}) is {} pixels wide, expected {}",
+                    col_idx, row_idx, width, expected_width
+                );
+                assert!(
+                    x % (1 << xdec) == 0 && y % (1 << ydec) == 0,
+                    "tile ({}, {}) at ({}, {}) does not start on a chroma sample",
+                    col_idx,
+                    row_idx,
+                    x,
+                    y
+                );
+
+                frame.blit(tile, x, y);
+                x += width;
+            }
+            assert_eq!(
+                x, full_width,
+                "tile widths add up to {}, expected {}",
+                x, full_width
+            );
+            y += height;
+        }
+        assert_eq!(
+            y, full_height,
+            "tile heights add up to {}, expected {}",
+            y, full_height
+        );
+
+        frame
+    }
+
+    /// Copies the visible pixels of `src` into the frame with their top-left
+    /// corner at the luma position (`dst_x`, `dst_y`).
+    ///
+    /// The parts of `src` that fall outside the visible area of the frame are
+    /// clipped. The chroma offsets are derived from the chroma decimation,
+    /// rounding down.
+    ///
+    /// # Panics
+    ///
+    /// - If the frames do not have the same chroma sampling
+    pub fn blit(&mut self, src: &Frame<T>, dst_x: usize, dst_y: usize) {
+        assert_eq!(
+            self.chroma_sampling(),
+            src.chroma_sampling(),
+            "frames must have the same chroma sampling"
+        );
+
+        for (dst, src) in self.planes.iter_mut().zip(src.planes.iter()) {
+            let x = dst_x >> dst.cfg.xdec;
+            let y = dst_y >> dst.cfg.ydec;
+            if x >= dst.cfg.width || y >= dst.cfg.height {
+                continue;
+            }
+            let width = src.cfg.width.min(dst.cfg.width - x);
+            let height = src.cfg.height.min(dst.cfg.height - y);
+
+            for (row_idx, src_row) in src.rows_iter().take(height).enumerate() {
+                let range = dst.row_range_cropped(x as isize, (y + row_idx) as isize);
+                dst.data[range][..width].copy_from_slice(&src_row[..width]);
+            }
+        }
+    }
+
+    /// Blends `src` into the frame, computing
+    /// `(1 - alpha) * self + alpha * src` for every visible sample.
+    ///
+    /// The result is rounded and clamped to the range of `T`. `alpha` is not
+    /// clamped, so values outside `0.0..=1.0` extrapolate away from one of the
+    /// frames.
+    ///
+    /// # Panics
+    ///
+    /// - If the planes of the frames do not have the same dimensions
+    pub fn blend(&mut self, src: &Frame<T>, alpha: f32) {
+        for (dst, src) in self.planes.iter_mut().zip(src.planes.iter()) {
+            assert!(
+                dst.cfg.width == src.cfg.width && dst.cfg.height == src.cfg.height,
+                "plane dimensions do not match ({}x{} != {}x{})",
+                dst.cfg.width,
+                dst.cfg.height,
+                src.cfg.width,
+                src.cfg.height
+            );
+
+            for (dst_row, src_row) in dst.rows_iter_mut().zip(src.rows_iter()) {
+                for (dst, &src) in dst_row.iter_mut().zip(src_row) {
+                    let blended = (1.0 - alpha).mul_add(dst.to_f32(), alpha * src.to_f32());
+                    *dst = T::from_f32(blended);
+                }
+            }
+        }
+    }
+
+    /// Returns a new frame interpolated between the frame and `next`,
+    /// computing `(1 - t) * self + t * next` for every visible sample.
+    ///
+    /// See [`Frame::blend`] for the rounding and clamping behavior.
+    ///
+    /// # Panics
+    ///
+    /// - If the frames do not have the same chroma sampling
+    /// - If the planes of the frames do not have the same dimensions
+    pub fn lerp(&self, next: &Frame<T>, t: f32) -> Frame<T> {
+        assert_eq!(
+            self.chroma_sampling(),
+            next.chroma_sampling(),
+            "frames must have the same chroma sampling"
+        );
+
+        let mut interpolated = self.clone();
+        interpolated.blend(next, t);
+        interpolated
+    }
+
+    /// Returns a new unpadded frame with half the width and height, each
+    /// output sample being the rounded average of a 2x2 block.
+    ///
+    /// Odd dimensions are handled by replicating the last row and column.
+    pub fn downscale_2(&self) -> Frame<T> {
+        self.downscale(2)
+    }
+
+    /// Returns a new unpadded frame with every plane downscaled by `factor`
+    /// with a box filter.
+    ///
+    /// Chroma planes are scaled by the same factor at their own resolution,
+    /// so the chroma sampling is preserved.
+    ///
+    /// # Panics
+    ///
+    /// - If `factor` is 0
+    pub fn downscale(&self, factor: usize) -> Frame<T> {
+        Frame {
+            planes: [
+                self.planes[0].downscale_n(factor),
+                self.planes[1].downscale_n(factor),
+                self.planes[2].downscale_n(factor),
+            ],
+        }
+    }
+
+    /// Returns a new unpadded frame resampled to `new_width` x `new_height`
+    /// with bilinear interpolation.
+    ///
+    /// The chroma planes are resampled to the chroma dimensions matching the
+    /// new luma dimensions.
+    pub fn resize(&self, new_width: usize, new_height: usize) -> Frame<T> {
+        let (chroma_width, chroma_height) = self
+            .chroma_sampling()
+            .get_chroma_dimensions(new_width, new_height);
+
+        Frame {
+            planes: [
+                self.planes[0].resize_bilinear(new_width, new_height),
+                self.planes[1].resize_bilinear(chroma_width, chroma_height),
+                self.planes[2].resize_bilinear(chroma_width, chroma_height),
+            ],
+        }
+    }
+
+    /// Returns a new frame with every plane mirrored left to right.
+    pub fn horizontal_flip(&self) -> Frame<T> {
+        let mut flipped = self.clone();
+        for (dst, src) in flipped.planes.iter_mut().zip(self.planes.iter()) {
+            for (dst_row, src_row) in dst.rows_iter_mut().zip(src.rows_iter()) {
+                for (dst, &src) in dst_row.iter_mut().zip(src_row.iter().rev()) {
+                    *dst = src;
+                }
+            }
+        }
+        flipped
+    }
+
+    /// Returns a new frame with every plane mirrored top to bottom.
+    pub fn vertical_flip(&self) -> Frame<T> {
+        let mut flipped = self.clone();
+        for (dst, src) in flipped.planes.iter_mut().zip(self.planes.iter()) {
+            let height = src.cfg.height;
+            for (y, dst_row) in dst.rows_iter_mut().enumerate() {
+                let range = src.row_range_cropped(0, (height - 1 - y) as isize);
+                dst_row.copy_from_slice(&src.data[range]);
+            }
+        }
+        flipped
+    }
+
+    /// Returns a new unpadded frame with the rows and columns of every plane
+    /// swapped.
+    ///
+    /// The chroma decimation axes are swapped too, so 4:2:2 becomes 4:4:0
+    /// and the other way around.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame uses 4:1:1 chroma sampling, which has no transposed
+    ///   counterpart
+    pub fn transpose(&self) -> Frame<T> {
+        assert!(
+            self.chroma_sampling() != ChromaSampling::Cs411,
+            "4:1:1 frames cannot be transposed"
+        );
 
         Frame {
             planes: [
-                Plane::new(luma_width, luma_height, 0, 0, luma_padding, luma_padding),
-                Plane::new(
-                    chroma_width,
-                    chroma_height,
-                    chroma_decimation_x,
-                    chroma_decimation_y,
-                    chroma_padding_x,
-                    chroma_padding_y,
-                ),
-                Plane::new(
-                    chroma_width,
-                    chroma_height,
-                    chroma_decimation_x,
-                    chroma_decimation_y,
-                    chroma_padding_x,
-                    chroma_padding_y,
-                ),
+                self.planes[0].transpose(),
+                self.planes[1].transpose(),
+                self.planes[2].transpose(),
             ],
         }
     }
+
+    /// Returns a new frame rotated clockwise by `rotation`.
+    ///
+    /// Rotating by 90 or 270 degrees swaps the dimensions and the chroma
+    /// decimation axes, as [`Frame::transpose`] does.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame uses 4:1:1 chroma sampling and is rotated by 90 or 270
+    ///   degrees
+    pub fn rotate(&self, rotation: Rotation) -> Frame<T> {
+        match rotation {
+            Rotation::R90 => self.transpose().horizontal_flip(),
+            Rotation::R180 => self.horizontal_flip().vertical_flip(),
+            Rotation::R270 => self.transpose().vertical_flip(),
+        }
+    }
+
+    /// Returns a new 4:2:0 frame, averaging the chroma samples of each 4:2:0
+    /// chroma block with rounding.
+    ///
+    /// The luma plane is copied unchanged. Blocks on the right and bottom
+    /// edges only average the samples they cover.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame is monochrome or uses 4:1:1 chroma sampling
+    pub fn to_420(&self) -> Frame<T> {
+        let (box_width, box_height) = match self.chroma_sampling() {
+            ChromaSampling::Cs444 => (2, 2),
+            ChromaSampling::Cs422 => (1, 2),
+            ChromaSampling::Cs440 => (2, 1),
+            ChromaSampling::Cs420 => (1, 1),
+            cs => panic!("cannot convert {} to 4:2:0", cs),
+        };
+
+        Frame {
+            planes: [
+                self.planes[0].clone(),
+                self.planes[1].box_downscale(box_width, box_height, 1, 1),
+                self.planes[2].box_downscale(box_width, box_height, 1, 1),
+            ],
+        }
+    }
+
+    /// Returns a new unpadded frame with every plane filtered with
+    /// [`Plane::median_filter`].
+    ///
+    /// The chroma planes use `radius` divided by their smallest subsampling
+    /// factor, so that the window covers about the same area as in the luma
+    /// plane.
+    pub fn median_filter(&self, radius: usize) -> Frame<T> {
+        let filter =
+            |plane: &Plane<T>| plane.median_filter(radius >> plane.cfg.xdec.min(plane.cfg.ydec));
+
+        Frame {
+            planes: [
+                filter(&self.planes[0]),
+                filter(&self.planes[1]),
+                filter(&self.planes[2]),
+            ],
+        }
+    }
+
+    /// Returns a new monochrome frame with a copy of the luma plane and empty
+    /// chroma planes, which allocate no memory.
+    pub fn to_luma_only(&self) -> Frame<T> {
+        Frame {
+            planes: [
+                self.planes[0].clone(),
+                Plane::new(0, 0, 0, 0, 0, 0),
+                Plane::new(0, 0, 0, 0, 0, 0),
+            ],
+        }
+    }
+
+    /// Returns a new 4:4:4 frame, upsampling the chroma planes to the luma
+    /// resolution with nearest-neighbor sampling.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame is monochrome
+    pub fn to_444(&self) -> Frame<T> {
+        assert!(
+            self.chroma_sampling() != ChromaSampling::Cs400,
+            "cannot upsample the chroma of a monochrome frame"
+        );
+
+        let luma = &self.planes[0];
+        let upsample = |src: &Plane<T>| {
+            let PlaneConfig {
+                width,
+                height,
+                xpad,
+                ypad,
+                ..
+            } = luma.cfg;
+            let PlaneConfig { xdec, ydec, .. } = src.cfg;
+            let mut dst = Plane::new(width, height, 0, 0, xpad, ypad);
+
+            for (y, dst_row) in dst.rows_iter_mut().enumerate() {
+                let src_row = &src.data[src.row_range_cropped(0, (y >> ydec) as isize)];
+                for (x, pixel) in dst_row.iter_mut().enumerate() {
+                    *pixel = src_row[x >> xdec];
+                }
+            }
+            dst.pad(width, height);
+
+            dst
+        };
+
+        Frame {
+            planes: [
+                luma.clone(),
+                upsample(&self.planes[1]),
+                upsample(&self.planes[2]),
+            ],
+        }
+    }
+
+    /// Returns a new 4:4:4 frame, upsampling the chroma planes to the luma
+    /// resolution with bilinear interpolation.
+    ///
+    /// The chroma samples are assumed to follow the MPEG-2 siting: co-sited
+    /// with the first luma column horizontally, and centered between the luma
+    /// rows they cover vertically. Positions outside the chroma plane are
+    /// clamped to its edges. See [`Frame::to_444`] for the cheaper
+    /// nearest-neighbor version.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame is monochrome
+    pub fn to_444_bilinear(&self) -> Frame<T> {
+        assert!(
+            self.chroma_sampling() != ChromaSampling::Cs400,
+            "cannot upsample the chroma of a monochrome frame"
+        );
+
+        let luma = &self.planes[0];
+        let upsample = |src: &Plane<T>| {
+            let PlaneConfig {
+                width,
+                height,
+                xpad,
+                ypad,
+                ..
+            } = luma.cfg;
+            let PlaneConfig { xdec, ydec, .. } = src.cfg;
+            let mut dst = Plane::new(width, height, 0, 0, xpad, ypad);
+            if src.cfg.width == 0 || src.cfg.height == 0 {
+                return dst;
+            }
+
+            // Returns the two nearest source indices and the weight of the
+            // second one, for a position in source coordinates
+            let taps = |pos: f32, len: usize| {
+                let pos = pos.clamp(0.0, (len - 1) as f32);
+                let i0 = pos as usize;
+                (i0, (i0 + 1).min(len - 1), pos - i0 as f32)
+            };
+            let (xscale, yscale) = ((1 << xdec) as f32, (1 << ydec) as f32);
+            let src = src.as_region_slice();
+
+            for (y, dst_row) in dst.rows_iter_mut().enumerate() {
+                let (y0, y1, fy) = taps((y as f32 + 0.5) / yscale - 0.5, src.height());
+                let (row0, row1) = (src.row(y0), src.row(y1));
+                for (x, pixel) in dst_row.iter_mut().enumerate() {
+                    let (x0, x1, fx) = taps(x as f32 / xscale, src.width());
+                    let top = fx.mul_add(row0[x1].to_f32() - row0[x0].to_f32(), row0[x0].to_f32());
+                    let bottom =
+                        fx.mul_add(row1[x1].to_f32() - row1[x0].to_f32(), row1[x0].to_f32());
+                    *pixel = T::from_f32(fy.mul_add(bottom - top, top));
+                }
+            }
+            dst.pad(width, height);
+
+            dst
+        };
+
+        Frame {
+            planes: [
+                luma.clone(),
+                upsample(&self.planes[1]),
+                upsample(&self.planes[2]),
+            ],
+        }
+    }
+
+    /// Returns a new unpadded frame holding the absolute difference between
+    /// the visible pixels of the two frames.
+    ///
+    /// # Panics
+    ///
+    /// - If the planes of the frames do not have the same dimensions
+    pub fn abs_diff(&self, other: &Frame<T>) -> Frame<T> {
+        Frame {
+            planes: [
+                self.planes[0].abs_diff(&other.planes[0]),
+                self.planes[1].abs_diff(&other.planes[1]),
+                self.planes[2].abs_diff(&other.planes[2]),
+            ],
+        }
+    }
+
+    /// Computes the sum of absolute differences between the visible pixels
+    /// of the two frames, over all planes.
+    ///
+    /// # Panics
+    ///
+    /// - If the planes of the frames do not have the same dimensions
+    pub fn frame_sad(&self, other: &Frame<T>) -> u64 {
+        self.planes
+            .iter()
+            .zip(other.planes.iter())
+            .map(|(a, b)| a.sad(b))
+            .sum()
+    }
+
+    /// Splits an interlaced frame into its top field, made of the even rows,
+    /// and its bottom field, made of the odd rows, as new unpadded frames of
+    /// half the height.
+    ///
+    /// The chroma planes are split the same way at their own, subsampled
+    /// row count.
+    ///
+    /// # Panics
+    ///
+    /// - If the height of a plane is odd
+    pub fn split_fields(&self) -> (Frame<T>, Frame<T>) {
+        let chroma_sampling = self.chroma_sampling();
+        let luma = &self.planes[0].cfg;
+        for plane in self.planes.iter() {
+            assert!(
+                plane.cfg.height % 2 == 0,
+                "plane height ({}) cannot be split into fields",
+                plane.cfg.height
+            );
+        }
+
+        let mut fields = [
+            Frame::new_unaligned(luma.width, luma.height / 2, chroma_sampling),
+            Frame::new_unaligned(luma.width, luma.height / 2, chroma_sampling),
+        ];
+        for (parity, field) in fields.iter_mut().enumerate() {
+            for (dst, src) in field.planes.iter_mut().zip(self.planes.iter()) {
+                let src_rows = src.rows_iter().skip(parity).step_by(2);
+                for (dst_row, src_row) in dst.rows_iter_mut().zip(src_rows) {
+                    dst_row.copy_from_slice(src_row);
+                }
+            }
+        }
+
+        let [top, bottom] = fields;
+        (top, bottom)
+    }
+
+    /// Returns whether every visible sample of the frame differs from the
+    /// corresponding sample of `other` by at most `max_abs_diff`.
+    ///
+    /// Frames with different dimensions or chroma sampling are never equal.
+    /// Use [`Frame::approx_eq_report`] to find the first sample that differs.
+    pub fn approx_eq(&self, other: &Frame<T>, max_abs_diff: u32) -> bool {
+        matches!(self.approx_eq_report(other, max_abs_diff), Ok(None))
+    }
+
+    /// Compares the frames like [`Frame::approx_eq`], and returns the first
+    /// sample, in plane and row-major order, that differs by more than
+    /// `max_abs_diff`, or `None` if there is none.
+    ///
+    /// # Errors
+    ///
+    /// - [`FrameError::DimensionMismatch`] if the visible dimensions or
+    ///   decimation of the planes do not match
+    pub fn approx_eq_report(
+        &self,
+        other: &Frame<T>,
+        max_abs_diff: u32,
+    ) -> Result<Option<SampleMismatch<T>>, FrameError> {
+        for (a, b) in self.planes.iter().zip(other.planes.iter()) {
+            if a.cfg.width != b.cfg.width
+                || a.cfg.height != b.cfg.height
+                || a.cfg.xdec != b.cfg.xdec
+                || a.cfg.ydec != b.cfg.ydec
+            {
+                return Err(FrameError::DimensionMismatch);
+            }
+        }
+
+        for (plane, (a, b)) in self.planes.iter().zip(other.planes.iter()).enumerate() {
+            for (y, (row_a, row_b)) in a.rows_iter().zip(b.rows_iter()).enumerate() {
+                for (x, (&pa, &pb)) in row_a.iter().zip(row_b).enumerate() {
+                    if u32::cast_from(pa).abs_diff(u32::cast_from(pb)) > max_abs_diff {
+                        return Ok(Some(SampleMismatch {
+                            plane,
+                            x,
+                            y,
+                            left: pa,
+                            right: pb,
+                        }));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Computes the PSNR of each plane against `other`, in dB.
+    ///
+    /// Planes that are identical or empty have an infinite PSNR.
+    ///
+    /// # Errors
+    ///
+    /// - If the visible dimensions of the planes do not match
+    pub fn psnr(&self, other: &Frame<T>, bit_depth: usize) -> Result<[f64; 3], FrameError> {
+        let max = ((1u64 << bit_depth) - 1) as f64;
+        let mut psnr = [0.0; 3];
+
+        for (out, (a, b)) in psnr
+            .iter_mut()
+            .zip(self.planes.iter().zip(other.planes.iter()))
+        {
+            if a.cfg.width != b.cfg.width || a.cfg.height != b.cfg.height {
+                return Err(FrameError::DimensionMismatch);
+            }
+
+            let mut sse = 0u64;
+            for (row_a, row_b) in a.rows_iter().zip(b.rows_iter()) {
+                for (&pa, &pb) in row_a.iter().zip(row_b) {
+                    let diff = i32::cast_from(pa) - i32::cast_from(pb);
+                    sse += (diff * diff) as u64;
+                }
+            }
+
+            *out = if sse == 0 {
+                f64::INFINITY
+            } else {
+                let mse = sse as f64 / (a.cfg.width * a.cfg.height) as f64;
+                10.0 * (max * max / mse).log10()
+            };
+        }
+
+        Ok(psnr)
+    }
+
+    /// Computes the PSNR of the whole frame against `other`, in dB.
+    ///
+    /// The plane PSNRs are weighted by their number of samples, which gives
+    /// the usual 4:1:1 weighting for 4:2:0 frames.
+    ///
+    /// # Errors
+    ///
+    /// - If the visible dimensions of the planes do not match
+    pub fn psnr_overall(&self, other: &Frame<T>, bit_depth: usize) -> Result<f64, FrameError> {
+        let psnr = self.psnr(other, bit_depth)?;
+        let samples = self
+            .planes
+            .iter()
+            .map(|p| (p.cfg.width * p.cfg.height) as f64);
+        let total: f64 = samples.clone().sum();
+
+        Ok(psnr
+            .iter()
+            .zip(samples)
+            .filter(|&(_, n)| n > 0.0)
+            .map(|(psnr, n)| psnr * n / total)
+            .sum())
+    }
+
+    /// Sets every allocated pixel of every plane, including the padding, to
+    /// `value`.
+    pub fn fill(&mut self, value: T) {
+        for plane in self.planes.iter_mut() {
+            plane.fill(value);
+        }
+    }
+
+    /// Sets every allocated pixel of every plane, including the padding, to
+    /// `value`, splitting the rows across the rayon thread pool.
+    ///
+    /// The result is identical to [`Frame::fill`].
+    #[cfg(feature = "rayon")]
+    pub fn par_fill(&mut self, value: T) {
+        use rayon::prelude::*;
+
+        for plane in self.planes.iter_mut() {
+            let stride = plane.cfg.stride;
+            if stride == 0 {
+                continue;
+            }
+            plane
+                .data
+                .par_chunks_mut(stride)
+                .for_each(|row| row.fill(value));
+        }
+    }
+
+    /// Returns whether both frames have the same dimensions, chroma sampling
+    /// and visible pixels, regardless of their stride and padding.
+    pub fn visible_eq(&self, other: &Frame<T>) -> bool {
+        self.planes.iter().zip(other.planes.iter()).all(|(a, b)| {
+            a.cfg.width == b.cfg.width
+                && a.cfg.height == b.cfg.height
+                && a.cfg.xdec == b.cfg.xdec
+                && a.cfg.ydec == b.cfg.ydec
+                && a.rows_iter().eq(b.rows_iter())
+        })
+    }
+
+    /// Returns a hash of the dimensions, chroma sampling and visible pixels of
+    /// the frame.
+    ///
+    /// The hash ignores the stride and padding, so frames that are
+    /// [`visible_eq`](Frame::visible_eq) hash equally. It uses a fixed
+    /// non-cryptographic function, so it is stable across runs.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = FxHasher::default();
+        self.hash_visible(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_visible<H: Hasher>(&self, state: &mut H) {
+        state.write_u8(self.chroma_sampling() as u8);
+        let mut buf = Vec::with_capacity(self.planes[0].cfg.width * 2);
+        for plane in self.planes.iter() {
+            state.write_usize(plane.cfg.width);
+            state.write_usize(plane.cfg.height);
+            for row in plane.rows_iter() {
+                buf.clear();
+                for &pixel in row {
+                    buf.extend_from_slice(&u16::cast_from(pixel).to_le_bytes());
+                }
+                state.write(&buf);
+            }
+        }
+    }
+
+    /// Returns a new frame with the same geometry and chroma sampling, with
+    /// `f` applied to every visible pixel.
+    ///
+    /// `f` is not called on the padding, which is instead replicated from the
+    /// edges of the new planes.
+    pub fn map<U: Pixel>(&self, f: impl Fn(T) -> U) -> Frame<U> {
+        Frame {
+            planes: [
+                map_plane(&self.planes[0], &f),
+                map_plane(&self.planes[1], &f),
+                map_plane(&self.planes[2], &f),
+            ],
+        }
+    }
+
+    /// Calls `f` with the plane index, the visible coordinates `(x, y)` in
+    /// that plane and a mutable reference to every visible pixel of every
+    /// plane. See [`Plane::for_each_pixel_mut`].
+    pub fn for_each_pixel_mut(&mut self, mut f: impl FnMut(usize, usize, usize, &mut T)) {
+        for (pli, plane) in self.planes.iter_mut().enumerate() {
+            plane.for_each_pixel_mut(|x, y, pixel| f(pli, x, y, pixel));
+        }
+    }
+
+    /// Returns a new frame with `f` applied to every visible pixel, like
+    /// [`Frame::map`], splitting the rows across the rayon thread pool.
+    ///
+    /// The result is identical to [`Frame::map`].
+    #[cfg(feature = "rayon")]
+    pub fn par_map<U: Pixel>(&self, f: impl Fn(T) -> U + Sync) -> Frame<U> {
+        Frame {
+            planes: [
+                par_map_plane(&self.planes[0], &f),
+                par_map_plane(&self.planes[1], &f),
+                par_map_plane(&self.planes[2], &f),
+            ],
+        }
+    }
+
+    /// Clamps every visible pixel to the range `0..=(1 << bit_depth) - 1`.
+    ///
+    /// The padding is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is 0 or larger than the size of `T` in bits
+    pub fn clamp_to_bit_depth(&mut self, bit_depth: usize) {
+        let max = Self::max_value(bit_depth);
+        for plane in self.planes.iter_mut() {
+            for row in plane.rows_iter_mut() {
+                for pixel in row {
+                    *pixel = (*pixel).min(max);
+                }
+            }
+        }
+    }
+
+    /// Returns a new frame with every visible sample rescaled from the full
+    /// range of `from_bit_depth` bits to the full range of `to_bit_depth`
+    /// bits, as `round(v * (2^to - 1) / (2^from - 1))`.
+    ///
+    /// Unlike [`Frame::upconvert`] and [`Frame::downconvert`], which shift by
+    /// a power of two, this maps the largest value of one bit depth exactly
+    /// onto the largest value of the other, as needed to compare frames of
+    /// different bit depths. Samples that exceed `from_bit_depth` are clamped
+    /// first.
+    ///
+    /// # Panics
+    ///
+    /// - If a bit depth is 0 or larger than the size of `T` in bits
+    pub fn normalize_to_bit_depth(&self, from_bit_depth: usize, to_bit_depth: usize) -> Frame<T> {
+        let from_max = u32::cast_from(Self::max_value(from_bit_depth)) as u64;
+        let to_max = u32::cast_from(Self::max_value(to_bit_depth)) as u64;
+
+        self.map(|v| {
+            let v = (u32::cast_from(v) as u64).min(from_max);
+            T::cast_from(((v * to_max + from_max / 2) / from_max) as u32)
+        })
+    }
+
+    /// Creates a read-only view of tightly packed planar data without
+    /// copying it.
+    ///
+    /// Each slice holds the rows of one plane back to back, with the chroma
+    /// planes sized according to `chroma_sampling`. Longer slices are
+    /// accepted and the trailing data is ignored.
+    ///
+    /// # Panics
+    ///
+    /// - If one of the slices is too short for its plane
+    pub fn borrow_planar<'a>(
+        data: [&'a [T]; 3],
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+    ) -> FrameRef<'a, T> {
+        let sizes = chroma_sampling.plane_sizes(width, height);
+        let region = |pli: usize| {
+            let (width, height) = sizes[pli];
+            PlaneRegion::new(data[pli], width, width, height)
+        };
+
+        FrameRef {
+            planes: [region(0), region(1), region(2)],
+            chroma_sampling,
+        }
+    }
+
+    /// Computes the statistics of each plane.
+    ///
+    /// See [`Plane::stats`].
+    pub fn plane_stats(&self) -> [Option<PlaneStats<T>>; 3] {
+        [
+            self.planes[0].stats(),
+            self.planes[1].stats(),
+            self.planes[2].stats(),
+        ]
+    }
+
+    /// Computes the CRC-32 of every visible row of each plane.
+    ///
+    /// See [`Plane::row_checksums`].
+    pub fn checksums(&self) -> [Vec<u32>; 3] {
+        [
+            self.planes[0].row_checksums(),
+            self.planes[1].row_checksums(),
+            self.planes[2].row_checksums(),
+        ]
+    }
+
+    /// Counts the occurrences of each luma sample value.
+    ///
+    /// See [`Plane::histogram`].
+    ///
+    /// # Panics
+    ///
+    /// - If a luma value does not fit in `bit_depth` bits
+    pub fn luma_histogram(&self, bit_depth: usize) -> Vec<u32> {
+        self.planes[0].histogram(bit_depth)
+    }
+
+    /// Fills the padding of every plane by replicating the edge pixels.
+    ///
+    /// `width` and `height` are the luma dimensions of the visible area; the
+    /// chroma planes use the correspondingly decimated dimensions.
+    pub fn pad(&mut self, width: usize, height: usize) {
+        for plane in self.planes.iter_mut() {
+            plane.pad(width, height);
+        }
+    }
+
+    /// Fills the padding of every plane by replicating the edge pixels of its
+    /// visible area.
+    ///
+    /// Unlike [`Frame::pad`], the visible dimensions are taken from the plane
+    /// configurations, which makes this suitable for refreshing the borders
+    /// after the visible pixels were modified.
+    pub fn extend_padding(&mut self) {
+        for plane in self.planes.iter_mut() {
+            plane.extend_padding();
+        }
+    }
+
+    /// Returns whether the frame is black: every luma pixel is at or below
+    /// the limited range black level, 16 scaled to `bit_depth`, and every
+    /// chroma pixel is within 4 (scaled to `bit_depth`) of the neutral
+    /// midpoint.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is less than 8 or larger than the size of `T` in bits
+    pub fn is_black(&self, bit_depth: usize) -> bool {
+        assert!(
+            (8..=T::BITS as usize).contains(&bit_depth),
+            "invalid bit depth ({}) for a {}-bit pixel",
+            bit_depth,
+            T::BITS
+        );
+        let shift = bit_depth - 8;
+        let black = 16u32 << shift;
+        let neutral = 128u32 << shift;
+        let tolerance = 4u32 << shift;
+
+        let is_black = |pli: usize, p: T| {
+            let p = u32::cast_from(p);
+            if pli == 0 {
+                p <= black
+            } else {
+                p.abs_diff(neutral) <= tolerance
+            }
+        };
+        self.planes.iter().enumerate().all(|(pli, plane)| {
+            plane
+                .rows_iter()
+                .all(|row| row.iter().all(|&p| is_black(pli, p)))
+        })
+    }
+
+    /// Returns a luma-sized mask that is 255 where the chroma of the frame is
+    /// within `tolerance` of the `(U, V)` key color `center`, and 0
+    /// elsewhere.
+    ///
+    /// The distance is the Euclidean distance in the UV plane. Subsampled
+    /// chroma is upsampled with nearest-neighbor sampling, so every luma pixel
+    /// covered by a chroma sample gets the same mask value.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame is monochrome
+    pub fn chroma_key_mask(&self, center: (T, T), tolerance: u32) -> Plane<u8> {
+        let (cb, cr) = self.chroma().expect("chroma keying requires chroma planes");
+        let (xdec, ydec) = (cb.cfg.xdec, cb.cfg.ydec);
+        let center: (i32, i32) = (center.0.into(), center.1.into());
+        let max_distance = (tolerance as u64).pow(2);
+
+        let luma = &self.planes[0].cfg;
+        let mut mask = Plane::new(luma.width, luma.height, 0, 0, 0, 0);
+        for (y, row) in mask.rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let du = i32::cast_from(cb.p(x >> xdec, y >> ydec)) - center.0;
+                let dv = i32::cast_from(cr.p(x >> xdec, y >> ydec)) - center.1;
+                let distance = (du as i64).pow(2) as u64 + (dv as i64).pow(2) as u64;
+                *pixel = if distance <= max_distance { 255 } else { 0 };
+            }
+        }
+
+        mask
+    }
+
+    /// Swaps the planes of the frame with the planes of `other`, without
+    /// copying the pixels. See [`Plane::swap`].
+    ///
+    /// # Panics
+    ///
+    /// - If the visible dimensions or chroma sampling of the frames do not
+    ///   match
+    pub fn swap(&mut self, other: &mut Frame<T>) {
+        for (a, b) in self.planes.iter().zip(other.planes.iter()) {
+            a.assert_same_geometry(b);
+        }
+        mem::swap(&mut self.planes, &mut other.planes);
+    }
+
+    /// Adds `delta` to every visible luma pixel, clamping the results to the
+    /// range of `bit_depth` bits. See [`Plane::offset`].
+    pub fn offset_luma(&mut self, delta: i32, bit_depth: usize) {
+        self.planes[0].offset(delta, bit_depth);
+    }
+
+    /// Maps every visible luma pixel through the lookup table `lut`. See
+    /// [`Plane::apply_lut`].
+    ///
+    /// # Panics
+    ///
+    /// - If `lut` is not a valid lookup table for the luma pixels
+    pub fn apply_luma_lut(&mut self, lut: &[T]) {
+        self.planes[0].apply_lut(lut);
+    }
+
+    /// Writes the visible area of the planes as raw planar data, in Y, U, V
+    /// order, with no stride padding.
+    ///
+    /// High bit depth samples are written in the byte order given by
+    /// `endian`. No chroma data is written for monochrome frames.
+    ///
+    /// # Errors
+    ///
+    /// - If writing to `writer` fails
+    #[cfg(feature = "std")]
+    pub fn write_planar<W: Write>(&self, writer: &mut W, endian: Endianness) -> io::Result<()> {
+        self.write_planar_ordered(writer, PlaneOrder::Yuv, endian)
+    }
+
+    /// Writes the visible area of the planes as raw planar data, like
+    /// [`Frame::write_planar`], with the chroma planes in the given order.
+    ///
+    /// # Errors
+    ///
+    /// - If writing to `writer` fails
+    #[cfg(feature = "std")]
+    pub fn write_planar_ordered<W: Write>(
+        &self,
+        writer: &mut W,
+        order: PlaneOrder,
+        endian: Endianness,
+    ) -> io::Result<()> {
+        let plane_count = if self.chroma_sampling() == ChromaSampling::Cs400 {
+            1
+        } else {
+            3
+        };
+        for &pli in &order.plane_indices()[..plane_count] {
+            write_plane(&self.planes[pli], writer, endian)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the visible area of the planes as raw planar data, in the
+    /// format written by [`Frame::write_planar_ordered`] with little-endian
+    /// samples.
+    ///
+    /// `PlaneOrder::Yuv` produces I420 and `PlaneOrder::Yvu` produces YV12
+    /// for 8-bit 4:2:0 frames.
+    #[cfg(feature = "std")]
+    pub fn to_planar(&self, order: PlaneOrder) -> Vec<u8> {
+        self.to_planar_with_endian(order, Endianness::Little)
+    }
+
+    /// Consumes the frame and returns the visible pixels of each plane,
+    /// tightly packed row after row.
+    ///
+    /// The padding and the stride alignment are discarded, so the pixels are
+    /// copied into new vectors, which takes time proportional to the number
+    /// of pixels. The chroma vectors of monochrome frames are empty.
+    pub fn into_planar_vecs(self) -> [Vec<T>; 3] {
+        self.planes.map(|plane| {
+            let mut pixels = Vec::with_capacity(plane.cfg.width * plane.cfg.height);
+            for row in plane.rows_iter() {
+                pixels.extend_from_slice(row);
+            }
+            pixels
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn to_planar_with_endian(&self, order: PlaneOrder, endian: Endianness) -> Vec<u8> {
+        let samples: usize = self.planes.iter().map(|p| p.cfg.width * p.cfg.height).sum();
+        let mut out = Vec::with_capacity(samples * mem::size_of::<T>());
+        // writing to a Vec cannot fail
+        self.write_planar_ordered(&mut out, order, endian).ok();
+        out
+    }
+
+    /// Reads a new unpadded frame from tightly packed raw planar data, in
+    /// Y, U, V order.
+    ///
+    /// High bit depth samples are read in the byte order given by `endian`
+    /// and masked to `bit_depth`. No chroma data is read for monochrome
+    /// frames.
+    ///
+    /// # Errors
+    ///
+    /// - If reading from `reader` fails, including `UnexpectedEof` if the
+    ///   stream ends before the frame is complete
+    #[cfg(feature = "std")]
+    pub fn read_planar<R: Read>(
+        reader: &mut R,
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        bit_depth: usize,
+        endian: Endianness,
+    ) -> io::Result<Self> {
+        Self::read_planar_ordered(
+            reader,
+            width,
+            height,
+            chroma_sampling,
+            bit_depth,
+            PlaneOrder::Yuv,
+            endian,
+        )
+    }
+
+    /// Reads a new unpadded frame from raw planar data, like
+    /// [`Frame::read_planar`], with the chroma planes in the given order.
+    ///
+    /// # Errors
+    ///
+    /// - If reading from `reader` fails, including `UnexpectedEof` if the
+    ///   stream ends before the frame is complete
+    #[cfg(feature = "std")]
+    pub fn read_planar_ordered<R: Read>(
+        reader: &mut R,
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        bit_depth: usize,
+        order: PlaneOrder,
+        endian: Endianness,
+    ) -> io::Result<Self> {
+        let mut frame = Frame::new_unaligned(width, height, chroma_sampling);
+        let bytes_per_pixel = mem::size_of::<T>();
+        let mask = u16::MAX >> (16 - bit_depth.clamp(1, 16));
+        let mut buf = vec![0u8; width * bytes_per_pixel];
+
+        let plane_count = if chroma_sampling == ChromaSampling::Cs400 {
+            1
+        } else {
+            3
+        };
+        for &pli in &order.plane_indices()[..plane_count] {
+            let plane = &mut frame.planes[pli];
+            let row_bytes = plane.cfg.width * bytes_per_pixel;
+            for row in plane.rows_iter_mut() {
+                let buf = &mut buf[..row_bytes];
+                reader.read_exact(buf)?;
+                match T::type_enum() {
+                    PixelType::U8 => {
+                        for (pixel, &byte) in row.iter_mut().zip(buf.iter()) {
+                            *pixel = T::cast_from(byte);
+                        }
+                    }
+                    PixelType::U16 => {
+                        for (pixel, bytes) in row.iter_mut().zip(buf.chunks_exact(2)) {
+                            let value = endian.u16_from_bytes([bytes[0], bytes[1]]) & mask;
+                            *pixel = T::cast_from(value);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(frame)
+    }
+
+    /// Size in bytes of the header written by [`Frame::to_bytes`].
+    #[cfg(feature = "std")]
+    const HEADER_LEN: usize = 10;
+
+    /// Serializes the frame into a compact binary format.
+    ///
+    /// The data starts with a header made of the luma width and height as
+    /// little-endian `u32`, the chroma sampling and the pixel size in bytes as
+    /// `u8`. It is followed by the visible area of the planes, in the format
+    /// written by [`Frame::write_planar`].
+    ///
+    /// # Panics
+    ///
+    /// - If the frame dimensions do not fit in a `u32`
+    #[cfg(feature = "std")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let luma = &self.planes[0].cfg;
+        let width = u32::try_from(luma.width).expect("frame width does not fit in a u32");
+        let height = u32::try_from(luma.height).expect("frame height does not fit in a u32");
+        let samples: usize = self.planes.iter().map(|p| p.cfg.width * p.cfg.height).sum();
+
+        let mut out = Vec::with_capacity(Self::HEADER_LEN + samples * mem::size_of::<T>());
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.push(self.chroma_sampling() as u8);
+        out.push(mem::size_of::<T>() as u8);
+        self.write_planar(&mut out, Endianness::Little)
+            .expect("writing to a Vec cannot fail");
+
+        out
+    }
+
+    /// Deserializes a new unpadded frame from the format written by
+    /// [`Frame::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// - [`FrameError::InvalidData`] if the header is malformed, the pixel
+    ///   size does not match `T`, or the length of the data does not match
+    ///   the header
+    #[cfg(feature = "std")]
+    pub fn from_bytes(data: &[u8]) -> Result<Self, FrameError> {
+        if data.len() < Self::HEADER_LEN {
+            return Err(FrameError::InvalidData);
+        }
+        let (header, mut planes) = data.split_at(Self::HEADER_LEN);
+        let width = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let height = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let chroma_sampling: ChromaSampling =
+            FromPrimitive::from_u8(header[8]).ok_or(FrameError::InvalidData)?;
+        if header[9] as usize != mem::size_of::<T>() {
+            return Err(FrameError::InvalidData);
+        }
+
+        // Check the length before allocating, so that a bogus header cannot
+        // trigger a huge allocation
+        let expected_len =
+            Self::planar_len(width, height, chroma_sampling).ok_or(FrameError::InvalidData)?;
+        if planes.len() != expected_len {
+            return Err(FrameError::InvalidData);
+        }
+
+        Self::read_planar(
+            &mut planes,
+            width,
+            height,
+            chroma_sampling,
+            T::BITS as usize,
+            Endianness::Little,
+        )
+        .map_err(|_| FrameError::InvalidData)
+    }
+
+    /// Returns the size in bytes of the raw planar data of a frame, or `None`
+    /// if it overflows `usize`.
+    #[cfg(feature = "std")]
+    fn planar_len(width: usize, height: usize, chroma_sampling: ChromaSampling) -> Option<usize> {
+        let (chroma_width, chroma_height) = chroma_sampling.get_chroma_dimensions(width, height);
+        chroma_width
+            .checked_mul(chroma_height)
+            .and_then(|chroma| chroma.checked_mul(2))
+            .zip(width.checked_mul(height))
+            .and_then(|(chroma, luma)| luma.checked_add(chroma))
+            .and_then(|samples| samples.checked_mul(mem::size_of::<T>()))
+    }
+
+    /// Magic number at the start of the format written by
+    /// [`Frame::to_tagged_bytes`].
+    #[cfg(feature = "std")]
+    const TAGGED_MAGIC: [u8; 4] = *b"VFRM";
+    /// Version of the format written by [`Frame::to_tagged_bytes`].
+    #[cfg(feature = "std")]
+    const TAGGED_VERSION: u8 = 1;
+    /// Size in bytes of the header fields written by
+    /// [`Frame::to_tagged_bytes`].
+    #[cfg(feature = "std")]
+    const TAGGED_FIELDS_LEN: u16 = 10;
+
+    /// Serializes the frame into a self-describing binary format, which is
+    /// read back with `Frame::try_from(&[u8])`.
+    ///
+    /// The data starts with the magic number `VFRM`, a version byte, and the
+    /// length of the following header fields as a little-endian `u16`. The
+    /// fields are the luma width and height as little-endian `u32`, the
+    /// chroma sampling and the pixel size in bytes as `u8`. Fields added
+    /// later are appended without changing the version, and readers skip
+    /// the fields they do not know using the length. Data with another
+    /// version is rejected. The header is followed by the visible area of
+    /// the planes, in the format written by [`Frame::write_planar`].
+    ///
+    /// # Panics
+    ///
+    /// - If the frame dimensions do not fit in a `u32`
+    #[cfg(feature = "std")]
+    pub fn to_tagged_bytes(&self) -> Vec<u8> {
+        let bytes = self.to_bytes();
+        let mut out = Vec::with_capacity(7 + bytes.len());
+        out.extend_from_slice(&Self::TAGGED_MAGIC);
+        out.push(Self::TAGGED_VERSION);
+        out.extend_from_slice(&Self::TAGGED_FIELDS_LEN.to_le_bytes());
+        // the header of `to_bytes` holds the same fields
+        out.extend_from_slice(&bytes);
+
+        out
+    }
+
+    /// Copies data into the frame planes from strided pixel arrays.
+    ///
+    /// Each source buffer is read row by row using its own stride, so it does
+    /// not need to match the stride of the destination plane.
+    ///
+    /// # Panics
+    ///
+    /// - If `bytes_per_pixel` is not 1 or 2, or does not fit in `T`
+    /// - If a source buffer is too short for the dimensions of its plane
+    pub fn copy_from_raw_u8(
+        &mut self,
+        data: [&[u8]; 3],
+        strides: [usize; 3],
+        bytes_per_pixel: usize,
+    ) {
+        assert!(
+            bytes_per_pixel == 1 || bytes_per_pixel == 2,
+            "unsupported bytes per pixel ({})",
+            bytes_per_pixel
+        );
+        assert!(
+            mem::size_of::<T>() >= bytes_per_pixel,
+            "source bytewidth ({}) cannot fit in Plane<u8>",
+            bytes_per_pixel
+        );
+
+        for (pli, plane) in self.planes.iter_mut().enumerate() {
+            let width = plane.cfg.width;
+            let height = plane.cfg.height;
+            let source = data[pli];
+            let source_stride = strides[pli];
+
+            if width == 0 || height == 0 {
+                continue;
+            }
+
+            let row_bytes = width * bytes_per_pixel;
+            assert!(
+                source_stride >= row_bytes,
+                "source stride ({}) of plane {} is smaller than its row size ({})",
+                source_stride,
+                pli,
+                row_bytes
+            );
+            let required = (height - 1) * source_stride + row_bytes;
+            assert!(
+                source.len() >= required,
+                "source buffer of plane {} is too short ({} < {})",
+                pli,
+                source.len(),
+                required
+            );
+
+            for (y, row) in plane.rows_iter_mut().enumerate() {
+                let source_row = &source[y * source_stride..][..row_bytes];
+                match bytes_per_pixel {
+                    1 => {
+                        for (pixel, byte) in row.iter_mut().zip(source_row) {
+                            *pixel = T::cast_from(*byte);
+                        }
+                    }
+                    _ => {
+                        for (pixel, bytes) in row.iter_mut().zip(source_row.chunks_exact(2)) {
+                            *pixel = T::cast_from(u16::from_le_bytes([bytes[0], bytes[1]]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Pixel> Default for Frame<T> {
+    /// Returns an empty 4:2:0 frame.
+    fn default() -> Self {
+        Frame::empty(ChromaSampling::default())
+    }
+}
+
+/// Read-only view of a frame borrowing its pixel data.
+///
+/// Created by [`Frame::borrow_planar`].
+#[derive(Debug, Clone, Copy)]
+pub struct FrameRef<'a, T: Pixel> {
+    /// Planes constituting the frame.
+    pub planes: [PlaneRegion<'a, T>; 3],
+    chroma_sampling: ChromaSampling,
+}
+
+impl<'a, T: Pixel> FrameRef<'a, T> {
+    /// Returns the chroma sampling of the frame.
+    pub fn chroma_sampling(&self) -> ChromaSampling {
+        self.chroma_sampling
+    }
+
+    /// Computes the statistics of each plane.
+    ///
+    /// See [`PlaneRegion::stats`].
+    pub fn plane_stats(&self) -> [Option<PlaneStats<T>>; 3] {
+        [
+            self.planes[0].stats(),
+            self.planes[1].stats(),
+            self.planes[2].stats(),
+        ]
+    }
+}
+
+/// Writes the visible area of `plane` as raw data with no stride padding.
+#[cfg(feature = "std")]
+fn write_plane<T: Pixel, W: Write>(
+    plane: &Plane<T>,
+    writer: &mut W,
+    endian: Endianness,
+) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(plane.cfg.width * mem::size_of::<T>());
+    for row in plane.rows_iter() {
+        buf.clear();
+        match T::type_enum() {
+            PixelType::U8 => buf.extend(row.iter().map(|&p| u8::cast_from(p))),
+            PixelType::U16 => {
+                for &p in row {
+                    buf.extend_from_slice(&endian.u16_to_bytes(u16::cast_from(p)));
+                }
+            }
+        }
+        writer.write_all(&buf)?;
+    }
+    Ok(())
+}
+
+/// A frame with an additional alpha plane.
+///
+/// The alpha plane always has the dimensions of the luma plane, regardless
+/// of the chroma sampling of the frame.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FrameWithAlpha<T: Pixel> {
+    /// The color planes.
+    pub frame: Frame<T>,
+    /// The alpha plane.
+    pub alpha: Plane<T>,
+}
+
+impl<T: Pixel> FrameWithAlpha<T> {
+    /// Creates a new frame with an alpha plane and no padding.
+    ///
+    /// Allocates data for the planes.
+    pub fn new(width: usize, height: usize, chroma_sampling: ChromaSampling) -> Self {
+        Self::new_with_padding(width, height, chroma_sampling, 0)
+    }
+
+    /// Creates a new frame with an alpha plane, see
+    /// [`Frame::new_with_padding`]. The alpha plane has the same padding as
+    /// the luma plane.
+    ///
+    /// Allocates data for the planes.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame is too large or the allocation fails
+    pub fn new_with_padding(
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        luma_padding: usize,
+    ) -> Self {
+        let frame = Frame::new_with_padding(width, height, chroma_sampling, luma_padding);
+        let alpha = frame.planes[0].clone();
+        FrameWithAlpha { frame, alpha }
+    }
+
+    /// Adds an alpha plane to `frame`.
+    ///
+    /// # Panics
+    ///
+    /// - If the visible dimensions of `alpha` do not match the luma plane of
+    ///   `frame`, or `alpha` is subsampled
+    pub fn from_parts(frame: Frame<T>, alpha: Plane<T>) -> Self {
+        let luma = &frame.planes[0].cfg;
+        assert!(
+            alpha.cfg.width == luma.width
+                && alpha.cfg.height == luma.height
+                && alpha.cfg.xdec == 0
+                && alpha.cfg.ydec == 0,
+            "alpha plane ({}x{}) does not match the luma plane ({}x{})",
+            alpha.cfg.width,
+            alpha.cfg.height,
+            luma.width,
+            luma.height
+        );
+        FrameWithAlpha { frame, alpha }
+    }
+
+    /// Writes the visible area of the planes as raw planar data, in the
+    /// format of [`Frame::write_planar`] followed by the alpha plane.
+    ///
+    /// # Errors
+    ///
+    /// - If writing to `writer` fails
+    #[cfg(feature = "std")]
+    pub fn write_planar<W: Write>(&self, writer: &mut W, endian: Endianness) -> io::Result<()> {
+        self.frame.write_planar(writer, endian)?;
+        write_plane(&self.alpha, writer, endian)
+    }
+}
+
+/// The hash function used by rustc, which is fast and deterministic.
+#[derive(Default)]
+struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for &byte in chunks.remainder() {
+            self.add_to_hash(byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Returns a plane with the same geometry as `src`, with every visible pixel
+/// converted through `f`, and the padding replicated from the edges.
+fn map_plane<T: Pixel, U: Pixel>(src: &Plane<T>, f: impl Fn(T) -> U) -> Plane<U> {
+    let PlaneConfig {
+        width,
+        height,
+        xdec,
+        ydec,
+        xpad,
+        ypad,
+        ..
+    } = src.cfg;
+    let mut dst = Plane::new(width, height, xdec, ydec, xpad, ypad);
+
+    for (dst_row, src_row) in dst.rows_iter_mut().zip(src.rows_iter()) {
+        for (dst_pixel, src_pixel) in dst_row.iter_mut().zip(src_row) {
+            *dst_pixel = f(*src_pixel);
+        }
+    }
+    if width > 0 && height > 0 {
+        dst.pad(width << xdec, height << ydec);
+    }
+
+    dst
+}
+
+#[cfg(feature = "rayon")]
+fn par_map_plane<T: Pixel, U: Pixel>(src: &Plane<T>, f: &(impl Fn(T) -> U + Sync)) -> Plane<U> {
+    use rayon::prelude::*;
+
+    let PlaneConfig {
+        width,
+        height,
+        xdec,
+        ydec,
+        xpad,
+        ypad,
+        ..
+    } = src.cfg;
+    let mut dst = Plane::new(width, height, xdec, ydec, xpad, ypad);
+    if width == 0 || height == 0 {
+        return dst;
+    }
+
+    let src_region = src.as_region_slice();
+    let PlaneConfig {
+        stride,
+        xorigin,
+        yorigin,
+        ..
+    } = dst.cfg;
+    dst.data
+        .par_chunks_mut(stride)
+        .skip(yorigin)
+        .take(height)
+        .enumerate()
+        .for_each(|(y, dst_row)| {
+            let dst_row = &mut dst_row[xorigin..xorigin + width];
+            for (dst_pixel, &src_pixel) in dst_row.iter_mut().zip(src_region.row(y)) {
+                *dst_pixel = f(src_pixel);
+            }
+        });
+    dst.pad(width << xdec, height << ydec);
+
+    dst
+}
+
+impl Frame<u8> {
+    /// Converts an 8-bit frame into a high bit depth frame by shifting every
+    /// sample left by `target_bit_depth - 8`.
+    ///
+    /// Use `Frame::<u16>::from` to change the pixel type without scaling.
+    ///
+    /// # Panics
+    ///
+    /// - If `target_bit_depth` is not in the range `8..=16`
+    pub fn upconvert(&self, target_bit_depth: usize) -> Frame<u16> {
+        assert!(
+            (8..=16).contains(&target_bit_depth),
+            "invalid target bit depth ({})",
+            target_bit_depth
+        );
+        let shift = target_bit_depth - 8;
+
+        self.map(|v| (v as u16) << shift)
+    }
+
+    /// Creates a 4:2:2 frame from packed YUYV data, where each pair of pixels
+    /// is stored as `Y0 U Y1 V`.
+    ///
+    /// # Panics
+    ///
+    /// - If `width` is odd
+    /// - If `data.len()` is not `width * height * 2`
+    pub fn from_yuyv(data: &[u8], width: usize, height: usize) -> Frame<u8> {
+        Self::from_packed_422(data, width, height, [0, 1, 2, 3])
+    }
+
+    /// Creates a 4:2:2 frame from packed UYVY data, where each pair of pixels
+    /// is stored as `U Y0 V Y1`.
+    ///
+    /// # Panics
+    ///
+    /// - If `width` is odd
+    /// - If `data.len()` is not `width * height * 2`
+    pub fn from_uyvy(data: &[u8], width: usize, height: usize) -> Frame<u8> {
+        Self::from_packed_422(data, width, height, [1, 0, 3, 2])
+    }
+
+    /// Creates a 4:2:0 frame from NV12 data, made of a luma plane followed by
+    /// a plane of interleaved `U V` samples.
+    ///
+    /// # Panics
+    ///
+    /// - If `y.len()` is not `width * height`
+    /// - If `uv.len()` does not match the interleaved 4:2:0 chroma dimensions
+    pub fn from_nv12(y: &[u8], uv: &[u8], width: usize, height: usize) -> Frame<u8> {
+        Self::from_semi_planar(y, uv, width, height, PlaneOrder::Yuv)
+    }
+
+    /// Creates a 4:2:0 frame from NV21 data, made of a luma plane followed by
+    /// a plane of interleaved `V U` samples.
+    ///
+    /// # Panics
+    ///
+    /// - If `y.len()` is not `width * height`
+    /// - If `vu.len()` does not match the interleaved 4:2:0 chroma dimensions
+    pub fn from_nv21(y: &[u8], vu: &[u8], width: usize, height: usize) -> Frame<u8> {
+        Self::from_semi_planar(y, vu, width, height, PlaneOrder::Yvu)
+    }
+
+    /// Returns the visible pixels of a 4:2:0 frame as NV12 luma and
+    /// interleaved `U V` buffers.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame does not use 4:2:0 chroma sampling
+    pub fn to_nv12(&self) -> (Vec<u8>, Vec<u8>) {
+        self.to_semi_planar(PlaneOrder::Yuv)
+    }
+
+    /// Returns the visible pixels of a 4:2:0 frame as NV21 luma and
+    /// interleaved `V U` buffers.
+    ///
+    /// # Panics
+    ///
+    /// - If the frame does not use 4:2:0 chroma sampling
+    pub fn to_nv21(&self) -> (Vec<u8>, Vec<u8>) {
+        self.to_semi_planar(PlaneOrder::Yvu)
+    }
+
+    fn semi_planar_name(order: PlaneOrder) -> &'static str {
+        match order {
+            PlaneOrder::Yuv => "NV12",
+            PlaneOrder::Yvu => "NV21",
+        }
+    }
+
+    /// Weaves two fields of tightly packed planar data into a progressive
+    /// frame of `width` x `height`, taking the even rows from `top` and the
+    /// odd rows from `bottom`. This is the inverse of
+    /// [`Frame::split_fields`].
+    ///
+    /// Each field holds the planes of a `width` x `height / 2` frame in Y, U,
+    /// V order. The chroma rows are interleaved at their subsampled row
+    /// count.
+    ///
+    /// # Panics
+    ///
+    /// - If a plane of the frame would have an odd height
+    /// - If the length of a field does not match its dimensions
+    pub fn from_interlaced_fields(
+        top: &[u8],
+        bottom: &[u8],
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+    ) -> Frame<u8> {
+        let mut frame = Frame::new_unaligned(width, height, chroma_sampling);
+        for plane in frame.planes.iter() {
+            assert!(
+                plane.cfg.height % 2 == 0,
+                "plane height ({}) cannot be woven from fields",
+                plane.cfg.height
+            );
+        }
+        let field_len = chroma_sampling.bytes_per_frame(width, height / 2, 1);
+        for field in [top, bottom] {
+            assert_eq!(
+                field.len(),
+                field_len,
+                "field data does not match the dimensions {}x{}",
+                width,
+                height / 2
+            );
+        }
+
+        let mut offset = 0;
+        for plane in frame.planes.iter_mut() {
+            let (plane_width, plane_height) = (plane.cfg.width, plane.cfg.height);
+            let plane_len = plane_width * plane_height / 2;
+            let row_len = plane_width.max(1);
+            let top_rows = top[offset..][..plane_len].chunks_exact(row_len);
+            let bottom_rows = bottom[offset..][..plane_len].chunks_exact(row_len);
+            let src_rows = top_rows
+                .zip(bottom_rows)
+                .flat_map(|(top, bottom)| [top, bottom]);
+            for (dst_row, src_row) in plane.rows_iter_mut().zip(src_rows) {
+                dst_row.copy_from_slice(src_row);
+            }
+            offset += plane_len;
+        }
+
+        frame
+    }
+
+    fn from_semi_planar(
+        y: &[u8],
+        chroma: &[u8],
+        width: usize,
+        height: usize,
+        order: PlaneOrder,
+    ) -> Self {
+        let name = Self::semi_planar_name(order);
+        let (chroma_width, chroma_height) =
+            ChromaSampling::Cs420.get_chroma_dimensions(width, height);
+        assert_eq!(
+            y.len(),
+            width * height,
+            "{} luma data has the wrong length",
+            name
+        );
+        assert_eq!(
+            chroma.len(),
+            chroma_width * chroma_height * 2,
+            "{} chroma data has the wrong length",
+            name
+        );
+
+        let mut frame = Frame::new_unaligned(width, height, ChromaSampling::Cs420);
+        for (dst_row, src_row) in frame.planes[0]
+            .rows_iter_mut()
+            .zip(y.chunks_exact(width.max(1)))
+        {
+            dst_row.copy_from_slice(src_row);
+        }
+        let [_, first, _] = order.plane_indices();
+        let [_, cb, cr] = &mut frame.planes;
+        let (first, second) = if first == 1 { (cb, cr) } else { (cr, cb) };
+        let rows = first.rows_iter_mut().zip(second.rows_iter_mut());
+        let src_rows = chroma.chunks_exact((chroma_width * 2).max(1));
+        for ((first_row, second_row), src_row) in rows.zip(src_rows) {
+            let pixels = first_row.iter_mut().zip(second_row);
+            for ((first, second), src) in pixels.zip(src_row.chunks_exact(2)) {
+                *first = src[0];
+                *second = src[1];
+            }
+        }
+
+        frame
+    }
+
+    fn to_semi_planar(&self, order: PlaneOrder) -> (Vec<u8>, Vec<u8>) {
+        assert_eq!(
+            self.chroma_sampling(),
+            ChromaSampling::Cs420,
+            "{} requires 4:2:0 chroma sampling",
+            Self::semi_planar_name(order)
+        );
+
+        let [luma_index, first, second] = order.plane_indices();
+        let luma = &self.planes[luma_index];
+        let (first, second) = (&self.planes[first], &self.planes[second]);
+        let mut y = Vec::with_capacity(luma.cfg.width * luma.cfg.height);
+        for row in luma.rows_iter() {
+            y.extend_from_slice(row);
+        }
+        let mut chroma = Vec::with_capacity(first.cfg.width * first.cfg.height * 2);
+        for (first_row, second_row) in first.rows_iter().zip(second.rows_iter()) {
+            for (&first, &second) in first_row.iter().zip(second_row) {
+                chroma.extend_from_slice(&[first, second]);
+            }
+        }
+
+        (y, chroma)
+    }
+
+    /// Deinterleaves packed 4:2:2 data, `offsets` giving the position of
+    /// `Y0`, `U`, `Y1` and `V` within each 4-byte group.
+    fn from_packed_422(data: &[u8], width: usize, height: usize, offsets: [usize; 4]) -> Self {
+        assert!(width.is_multiple_of(2), "packed 4:2:2 width must be even");
+        assert_eq!(
+            data.len(),
+            width * height * 2,
+            "packed 4:2:2 data has the wrong length"
+        );
+
+        let mut frame = Frame::new_unaligned(width, height, ChromaSampling::Cs422);
+        let [y0, u, y1, v] = offsets;
+        let [luma, cb, cr] = &mut frame.planes;
+        let rows = luma
+            .rows_iter_mut()
+            .zip(cb.rows_iter_mut())
+            .zip(cr.rows_iter_mut());
+        for (((luma_row, cb_row), cr_row), src_row) in rows.zip(data.chunks_exact(width * 2)) {
+            let pixels = luma_row.chunks_exact_mut(2).zip(cb_row).zip(cr_row);
+            for (((luma, cb), cr), src) in pixels.zip(src_row.chunks_exact(4)) {
+                luma[0] = src[y0];
+                luma[1] = src[y1];
+                *cb = src[u];
+                *cr = src[v];
+            }
+        }
+
+        frame
+    }
+}
+
+impl Frame<u16> {
+    /// Converts a high bit depth frame into an 8-bit frame by shifting every
+    /// sample right by `source_bit_depth - 8`, rounding to nearest.
+    ///
+    /// Samples that exceed `source_bit_depth` are clamped to 255. Use
+    /// `Frame::<u8>::try_from` to change the pixel type without scaling.
+    ///
+    /// # Panics
+    ///
+    /// - If `source_bit_depth` is not in the range `8..=16`
+    pub fn downconvert(&self, source_bit_depth: usize) -> Frame<u8> {
+        assert!(
+            (8..=16).contains(&source_bit_depth),
+            "invalid source bit depth ({})",
+            source_bit_depth
+        );
+        let shift = source_bit_depth - 8;
+        let bias = (1u32 << shift) >> 1;
+
+        self.map(|v: u16| ((v as u32 + bias) >> shift).min(255) as u8)
+    }
+}
+
+impl Frame<u16> {
+    /// Returns a new frame with the samples of `bit_depth` bits moved to the
+    /// most significant bits of the 16-bit words, as expected by MSB-aligned
+    /// formats such as P010.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is not in the range `1..=16`
+    pub fn pack_msb(&self, bit_depth: usize) -> Frame<u16> {
+        let max = Self::max_value(bit_depth);
+        let shift = 16 - bit_depth;
+        self.map(|v| v.min(max) << shift)
+    }
+
+    /// Returns a new frame with MSB-aligned samples moved back to the least
+    /// significant `bit_depth` bits. This is the inverse of
+    /// [`Frame::pack_msb`].
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is not in the range `1..=16`
+    pub fn unpack_msb(&self, bit_depth: usize) -> Frame<u16> {
+        assert!(
+            (1..=16).contains(&bit_depth),
+            "invalid bit depth ({})",
+            bit_depth
+        );
+        let shift = 16 - bit_depth;
+        self.map(|v| v >> shift)
+    }
+
+    /// Returns the visible samples of the planes, in Y, U, V order, packed as
+    /// 10-bit values with four samples in five bytes.
+    ///
+    /// The samples form a little-endian bit stream: the first sample occupies
+    /// the 10 least significant bits of the first 40-bit group, stored as 5
+    /// little-endian bytes. The last group is padded with zero samples.
+    /// Samples are truncated to 10 bits.
+    pub fn to_packed_10bit(&self) -> Vec<u8> {
+        let samples: usize = self.planes.iter().map(|p| p.cfg.width * p.cfg.height).sum();
+        let mut out = Vec::with_capacity(samples.div_ceil(4) * 5);
+
+        let mut group = 0u64;
+        let mut count = 0;
+        for plane in self.planes.iter() {
+            for pixel in plane.iter() {
+                group |= ((pixel & 0x3ff) as u64) << (count * 10);
+                count += 1;
+                if count == 4 {
+                    out.extend_from_slice(&group.to_le_bytes()[..5]);
+                    group = 0;
+                    count = 0;
+                }
+            }
+        }
+        if count > 0 {
+            out.extend_from_slice(&group.to_le_bytes()[..5]);
+        }
+
+        out
+    }
+
+    /// Returns the visible area of the planes as raw planar data in Y, U, V
+    /// order, with the samples in the given byte order.
+    ///
+    /// Unlike [`Frame::to_bytes`], no header is written.
+    #[cfg(feature = "std")]
+    pub fn to_bytes_with_endian(&self, endian: Endianness) -> Vec<u8> {
+        self.to_planar_with_endian(PlaneOrder::Yuv, endian)
+    }
+}
+
+/// Widens the samples without scaling them, so the values are unchanged.
+///
+/// Use [`Frame::upconvert`] to scale the samples to a higher bit depth.
+impl From<Frame<u8>> for Frame<u16> {
+    fn from(frame: Frame<u8>) -> Self {
+        frame.map(u16::from)
+    }
+}
+
+/// Narrows the samples without scaling them, failing with
+/// [`FrameError::SampleOutOfRange`] if a visible sample exceeds 255.
+///
+/// Use [`Frame::downconvert`] to scale the samples from a higher bit depth.
+impl TryFrom<Frame<u16>> for Frame<u8> {
+    type Error = FrameError;
+
+    fn try_from(frame: Frame<u16>) -> Result<Self, Self::Error> {
+        let in_range = frame
+            .planes
+            .iter()
+            .all(|plane| plane.rows_iter().flatten().all(|&v| v <= 255));
+        if !in_range {
+            return Err(FrameError::SampleOutOfRange);
+        }
+
+        Ok(frame.map(|v: u16| v as u8))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl {
+    use super::Frame;
+    use crate::pixel::{ChromaSampling, Pixel, PixelType};
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    /// Largest width and height of the generated frames.
+    const MAX_DIMENSION: usize = 256;
+
+    /// Generates unpadded frames of up to 256x256 pixels with any chroma
+    /// sampling, filled from the remaining input.
+    impl<'a, T: Pixel> Arbitrary<'a> for Frame<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let width = u.int_in_range(1..=MAX_DIMENSION)?;
+            let height = u.int_in_range(1..=MAX_DIMENSION)?;
+            let chroma_sampling = *u.choose(&[
+                ChromaSampling::Cs420,
+                ChromaSampling::Cs422,
+                ChromaSampling::Cs444,
+                ChromaSampling::Cs400,
+                ChromaSampling::Cs440,
+                ChromaSampling::Cs411,
+            ])?;
+
+            let mut frame = Frame::new_unaligned(width, height, chroma_sampling);
+            for plane in frame.planes.iter_mut() {
+                for row in plane.rows_iter_mut() {
+                    for pixel in row {
+                        *pixel = match T::type_enum() {
+                            PixelType::U8 => T::cast_from(u8::arbitrary(u)?),
+                            PixelType::U16 => T::cast_from(u16::arbitrary(u)?),
+                        };
+                    }
+                }
+            }
+
+            Ok(frame)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn arbitrary_frame() {
+            let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+            let mut u = Unstructured::new(&data);
+            let frame = Frame::<u16>::arbitrary(&mut u).unwrap();
+
+            let luma = &frame.planes[0].cfg;
+            assert!((1..=MAX_DIMENSION).contains(&luma.width));
+            assert!((1..=MAX_DIMENSION).contains(&luma.height));
+            let (chroma_width, chroma_height) = frame
+                .chroma_sampling()
+                .get_chroma_dimensions(luma.width, luma.height);
+            assert_eq!(frame.planes[1].cfg.width, chroma_width);
+            assert_eq!(frame.planes[2].cfg.height, chroma_height);
+
+            let frame = Frame::<u8>::arbitrary(&mut Unstructured::new(&[])).unwrap();
+            assert_eq!(frame.planes[0].cfg.width, 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns `frame` with every visible pixel set to `y * 16 + x`, truncated
+    /// to `T`.
+    fn filled<T: Pixel>(mut frame: Frame<T>) -> Frame<T> {
+        frame.for_each_pixel_mut(|_, x, y, pixel| *pixel = T::cast_from(y * 16 + x));
+        frame
+    }
+
+    #[test]
+    fn new_without_padding() {
+        for cs in [
+            ChromaSampling::Cs420,
+            ChromaSampling::Cs422,
+            ChromaSampling::Cs444,
+            ChromaSampling::Cs400,
+        ] {
+            let frame = Frame::<u8>::new(14, 6, cs);
+            let (chroma_width, chroma_height) = cs.get_chroma_dimensions(16, 8);
+            let [luma, cb, cr] = &frame.planes;
+
+            assert_eq!((luma.cfg.width, luma.cfg.height), (16, 8));
+            assert_eq!((cb.cfg.width, cb.cfg.height), (chroma_width, chroma_height));
+            for plane in &frame.planes {
+                assert_eq!((plane.cfg.xpad, plane.cfg.ypad), (0, 0));
+                assert_eq!((plane.cfg.xorigin, plane.cfg.yorigin), (0, 0));
+            }
+            assert_eq!(cr.cfg, cb.cfg);
+        }
+    }
+
+    #[test]
+    fn copy_from_raw_u8_strided() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+
+        let luma: Vec<u8> = (0..10 * 8).map(|i| i as u8).collect();
+        let chroma = vec![7u8; 12 * 4];
+
+        frame.copy_from_raw_u8([&luma, &chroma, &chroma], [10, 12, 12], 1);
+
+        for (y, row) in frame.planes[0].rows_iter().enumerate() {
+            let expected: Vec<u16> = (0..8).map(|x| (y * 10 + x) as u16).collect();
+            assert_eq!(&expected[..], row);
+        }
+        assert!(frame.planes[1].iter().all(|p| p == 7));
+    }
+
+    #[test]
+    fn pad_replicates_edges() {
+        let mut frame = filled(Frame::<u8>::new_with_padding(
+            8,
+            8,
+            ChromaSampling::Cs420,
+            16,
+        ));
+
+        frame.pad(8, 8);
+
+        for plane in frame.planes.iter() {
+            let PlaneConfig {
+                stride,
+                alloc_height,
+                width,
+                height,
+                xorigin,
+                yorigin,
+                ..
+            } = plane.cfg;
+            for y in 0..alloc_height {
+                for x in 0..stride {
+                    let vy = y.clamp(yorigin, yorigin + height - 1) - yorigin;
+                    let vx = x.clamp(xorigin, xorigin + width - 1) - xorigin;
+                    assert_eq!(plane.data[y * stride + x], (vy * 16 + vx) as u8);
+                }
+            }
+        }
+        assert_eq!(frame.planes[1].cfg.width, 4);
+        assert_eq!(frame.planes[1].cfg.ypad, 8);
+    }
+
+    #[test]
+    fn crop_odd_origin_420() {
+        let frame = filled(Frame::<u8>::new(16, 16, ChromaSampling::Cs420));
+
+        let cropped = frame.crop(3, 5, 7, 6);
+
+        assert_eq!(cropped.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(cropped.planes[0].cfg.width, 7);
+        assert_eq!(cropped.planes[0].cfg.height, 6);
+        assert_eq!(cropped.planes[0].p(0, 0), 5 * 16 + 3);
+        assert_eq!(cropped.planes[0].p(6, 5), 10 * 16 + 9);
+
+        for plane in &cropped.planes[1..] {
+            assert_eq!(plane.cfg.width, 4);
+            assert_eq!(plane.cfg.height, 3);
+            assert_eq!(plane.p(0, 0), 2 * 16 + 1);
+            assert_eq!(plane.p(3, 2), 4 * 16 + 4);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the frame dimensions")]
+    fn crop_out_of_bounds() {
+        let frame = Frame::<u8>::new(16, 16, ChromaSampling::Cs420);
+        frame.crop(8, 8, 9, 8);
+    }
+
+    #[test]
+    fn upconvert() {
+        let mut frame = Frame::<u8>::new_with_padding(8, 8, ChromaSampling::Cs420, 8);
+        frame.planes[0].data_origin_mut()[..3].copy_from_slice(&[0, 1, 255]);
+        frame.planes[1].data_origin_mut()[0] = 64;
+
+        let frame10 = frame.upconvert(10);
+        assert_eq!(frame10.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(frame10.planes[0].cfg.xpad, 8);
+        assert_eq!(&frame10.planes[0].data_origin()[..3], &[0, 4, 1020]);
+        assert_eq!(frame10.planes[1].p(0, 0), 256);
+        assert_eq!(frame10.planes[2].p(0, 0), 512);
+
+        let frame12 = frame.upconvert(12);
+        assert_eq!(&frame12.planes[0].data_origin()[..3], &[0, 16, 4080]);
+        assert_eq!(frame12.planes[1].p(0, 0), 1024);
+    }
+
+    #[test]
+    fn downconvert_rounding() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.planes[0].data_origin_mut()[..4].copy_from_slice(&[1, 2, 6, 1021]);
+
+        let frame8 = frame.downconvert(10);
+        assert_eq!(frame8.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(&frame8.planes[0].data_origin()[..4], &[0, 1, 2, 255]);
+        assert_eq!(frame8.planes[1].p(0, 0), 32);
+
+        let frame8 = frame.downconvert(12);
+        assert_eq!(&frame8.planes[0].data_origin()[..4], &[0, 0, 0, 64]);
+    }
+
+    #[test]
+    fn downconvert_clamps_out_of_range() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs444);
+        frame.planes[0].data_origin_mut()[..3].copy_from_slice(&[1023, 1024, 65535]);
+
+        let frame8 = frame.downconvert(10);
+        assert_eq!(&frame8.planes[0].data_origin()[..3], &[255, 255, 255]);
+
+        let frame8 = frame.downconvert(8);
+        assert_eq!(&frame8.planes[0].data_origin()[..3], &[255, 255, 255]);
+    }
+
+    #[test]
+    fn write_planar() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.planes[0].fill(0x0102);
+        frame.planes[1].fill(3);
+        frame.planes[2].fill(4);
+
+        let mut out = Vec::new();
+        frame.write_planar(&mut out, Endianness::Little).unwrap();
+
+        assert_eq!(out.len(), (8 * 8 + 2 * 4 * 4) * 2);
+        assert!(out[..128].chunks(2).all(|p| p == [2, 1]));
+        assert!(out[128..160].chunks(2).all(|p| p == [3, 0]));
+        assert!(out[160..].chunks(2).all(|p| p == [4, 0]));
+
+        let frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        let mut out = Vec::new();
+        frame.write_planar(&mut out, Endianness::Little).unwrap();
+        assert_eq!(out, [128; 64]);
+    }
+
+    #[test]
+    fn read_planar_round_trip() {
+        let data: Vec<u8> = (0..(6 * 5 + 2 * 3 * 3) * 2).map(|i| i as u8).collect();
+        let frame = Frame::<u16>::read_planar(
+            &mut &data[..],
+            6,
+            5,
+            ChromaSampling::Cs420,
+            10,
+            Endianness::Little,
+        )
+        .unwrap();
+
+        assert_eq!(frame.planes[0].cfg.width, 6);
+        assert_eq!(frame.planes[0].cfg.height, 5);
+        assert_eq!(frame.planes[1].cfg.width, 3);
+        assert_eq!(frame.planes[1].cfg.height, 3);
+        assert_eq!(frame.planes[0].p(0, 0), 0x0100);
+        assert_eq!(frame.planes[0].p(1, 0), 0x0302 & 0x3ff);
+
+        let mut out = Vec::new();
+        frame.write_planar(&mut out, Endianness::Little).unwrap();
+        let masked: Vec<u8> = data
+            .chunks(2)
+            .flat_map(|b| (u16::from_le_bytes([b[0], b[1]]) & 0x3ff).to_le_bytes())
+            .collect();
+        assert_eq!(out, masked);
+    }
+
+    #[test]
+    fn read_write_planar_big_endian() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.planes[0].fill(0x0102);
+
+        let mut out = Vec::new();
+        frame.write_planar(&mut out, Endianness::Big).unwrap();
+        assert!(out[..128].chunks(2).all(|p| p == [1, 2]));
+
+        let read = Frame::<u16>::read_planar(
+            &mut &out[..],
+            8,
+            8,
+            ChromaSampling::Cs420,
+            10,
+            Endianness::Big,
+        )
+        .unwrap();
+        assert_eq!(read.planes[0].p(7, 7), 0x0102);
+        assert!(read.visible_eq(&frame));
+    }
+
+    #[test]
+    fn read_planar_short_stream() {
+        let data = [0u8; 8 * 8 + 4 * 4];
+        let err = Frame::<u8>::read_planar(
+            &mut &data[..],
+            8,
+            8,
+            ChromaSampling::Cs420,
+            8,
+            Endianness::Little,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn downscale_2_odd() {
+        let data: Vec<u8> = [
+            &[10u8, 20, 30, 40, 50][..],
+            &[30, 40, 50, 60, 70],
+            &[0, 0, 100, 100, 9],
+        ]
+        .concat();
+        let chroma = [128u8; 6];
+        let frame = Frame::<u8>::read_planar(
+            &mut &[data, chroma.to_vec(), chroma.to_vec()].concat()[..],
+            5,
+            3,
+            ChromaSampling::Cs420,
+            8,
+            Endianness::Little,
+        )
+        .unwrap();
+
+        let half = frame.downscale_2();
+        assert_eq!(half.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(half.planes[0].cfg.width, 3);
+        assert_eq!(half.planes[0].cfg.height, 2);
+        assert_eq!(half.planes[1].cfg.width, 2);
+        assert_eq!(half.planes[1].cfg.height, 1);
+
+        let luma: Vec<u8> = half.planes[0].iter().collect();
+        assert_eq!(luma, [25, 45, 60, 0, 100, 9]);
+        assert!(half.planes[1].iter().all(|p| p == 128));
+    }
+
+    #[test]
+    fn downscale_ragged() {
+        let frame = Frame::<u16>::new(16, 16, ChromaSampling::Cs420);
+        let third = frame.downscale(3);
+
+        assert_eq!(third.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(third.planes[0].cfg.width, 6);
+        assert_eq!(third.planes[0].cfg.height, 6);
+        assert_eq!(third.planes[1].cfg.width, 3);
+        assert_eq!(third.planes[1].cfg.height, 3);
+        assert!(third.planes.iter().all(|p| p.iter().all(|v| v == 128)));
+    }
+
+    #[test]
+    fn resize() {
+        let frame = Frame::<u16>::new(16, 16, ChromaSampling::Cs420);
+        let resized = frame.resize(25, 9);
+
+        assert_eq!(resized.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(resized.planes[0].cfg.width, 25);
+        assert_eq!(resized.planes[0].cfg.height, 9);
+        assert_eq!(resized.planes[1].cfg.width, 13);
+        assert_eq!(resized.planes[1].cfg.height, 5);
+        assert!(resized.planes.iter().all(|p| p.iter().all(|v| v == 128)));
+
+        let mono = Frame::<u8>::new(8, 8, ChromaSampling::Cs400).resize(4, 4);
+        assert_eq!(mono.chroma_sampling(), ChromaSampling::Cs400);
+    }
+
+    #[test]
+    fn flips() {
+        // Mark the top-left 2x2 luma block and its co-located chroma sample
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        frame.fill(0);
+        for row in frame.planes[0].rows_iter_mut().take(2) {
+            row[..2].fill(1);
+        }
+        frame.planes[1].data_origin_mut()[0] = 2;
+        frame.planes[2].data_origin_mut()[0] = 3;
+
+        let h = frame.horizontal_flip();
+        assert_eq!(h.planes[0].p(7, 0), 1);
+        assert_eq!(h.planes[0].p(6, 1), 1);
+        assert_eq!(h.planes[0].p(0, 0), 0);
+        assert_eq!(h.planes[1].p(3, 0), 2);
+        assert_eq!(h.planes[2].p(3, 0), 3);
+        assert_eq!(h.planes[1].p(0, 0), 0);
+
+        let v = frame.vertical_flip();
+        assert_eq!(v.planes[0].p(0, 7), 1);
+        assert_eq!(v.planes[0].p(1, 6), 1);
+        assert_eq!(v.planes[0].p(0, 0), 0);
+        assert_eq!(v.planes[1].p(0, 3), 2);
+        assert_eq!(v.planes[2].p(0, 3), 3);
+        assert_eq!(v.planes[1].p(0, 0), 0);
+
+        let hv = h.vertical_flip();
+        assert_eq!(hv.planes[0].p(7, 7), 1);
+        assert_eq!(hv.planes[1].p(3, 3), 2);
+    }
+
+    #[test]
+    fn to_444() {
+        let mut frame = Frame::<u8>::new_with_padding(8, 8, ChromaSampling::Cs422, 8);
+        for (y, row) in frame.planes[1].rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = (y * 4 + x) as u8;
+            }
+        }
+
+        let full = frame.to_444();
+        assert_eq!(full.chroma_sampling(), ChromaSampling::Cs444);
+        assert_eq!(full.planes[1].cfg, full.planes[0].cfg);
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(full.planes[1].p(x, y), (y * 4 + x / 2) as u8);
+                assert_eq!(full.planes[2].p(x, y), 128);
+            }
+        }
+    }
+
+    #[test]
+    fn psnr() {
+        let frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        assert_eq!(
+            frame.psnr(&frame.clone(), 8),
+            Ok([f64::INFINITY, f64::INFINITY, f64::INFINITY])
+        );
+        assert_eq!(frame.psnr_overall(&frame.clone(), 8), Ok(f64::INFINITY));
+
+        let mut other = frame.clone();
+        other.planes[0].data_origin_mut()[0] = 138;
+        let psnr = frame.psnr(&other, 8).unwrap();
+        // MSE of 100 / 64
+        let expected = 10.0 * (255.0f64 * 255.0 * 64.0 / 100.0).log10();
+        assert!((psnr[0] - expected).abs() < 1e-9);
+        assert_eq!(&psnr[1..], &[f64::INFINITY, f64::INFINITY]);
+
+        let mono = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        let mut other = mono.clone();
+        other.planes[0].data_origin_mut()[0] = 138;
+        let overall = mono.psnr_overall(&other, 8).unwrap();
+        assert!((overall - expected).abs() < 1e-9);
+
+        let small = Frame::<u8>::new(16, 8, ChromaSampling::Cs420);
+        assert_eq!(frame.psnr(&small, 8), Err(FrameError::DimensionMismatch));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_map() {
+        let frame = filled(Frame::<u8>::new_with_padding(
+            64,
+            48,
+            ChromaSampling::Cs420,
+            8,
+        ));
+
+        let to_10_bit = |p: u8| (p as u16) << 2;
+        assert_eq!(frame.par_map(to_10_bit), frame.map(to_10_bit));
+
+        let mono = Frame::<u16>::new(16, 16, ChromaSampling::Cs400);
+        assert_eq!(mono.par_map(|p| p + 1), mono.map(|p| p + 1));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_fill() {
+        let mut serial = Frame::<u16>::new_with_padding(64, 48, ChromaSampling::Cs420, 8);
+        let mut parallel = serial.clone();
+
+        serial.fill(1023);
+        parallel.par_fill(1023);
+
+        for (a, b) in serial.planes.iter().zip(parallel.planes.iter()) {
+            assert_eq!(a.cfg, b.cfg);
+            assert_eq!(&a.data[..], &b.data[..]);
+        }
+        assert!(parallel.planes[2].data.iter().all(|&p| p == 1023));
+    }
+
+    #[test]
+    fn try_new_with_padding() {
+        let frame =
+            Frame::<u16>::try_new_with_padding(16, 16, ChromaSampling::Cs420, 8, None).unwrap();
+        assert_eq!(frame.planes[1].cfg.xpad, 4);
+
+        let bytes: usize = frame
+            .planes
+            .iter()
+            .map(|p| p.cfg.stride * p.cfg.alloc_height * 2)
+            .sum();
+        assert!(
+            Frame::<u16>::try_new_with_padding(16, 16, ChromaSampling::Cs420, 8, Some(bytes))
+                .is_ok()
+        );
+        assert_eq!(
+            Frame::<u16>::try_new_with_padding(16, 16, ChromaSampling::Cs420, 8, Some(bytes - 1)),
+            Err(FrameError::TooLarge)
+        );
+
+        assert_eq!(
+            Frame::<u8>::try_new_with_padding(usize::MAX - 1, 16, ChromaSampling::Cs420, 0, None),
+            Err(FrameError::TooLarge)
+        );
+        assert_eq!(
+            Frame::<u16>::try_new_with_padding(1 << 32, 1 << 32, ChromaSampling::Cs444, 0, None),
+            Err(FrameError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn try_new_with_padding_validates_dimensions() {
+        for (width, height, chroma_sampling, valid) in [
+            (7, 8, ChromaSampling::Cs420, false),
+            (8, 7, ChromaSampling::Cs420, false),
+            (7, 7, ChromaSampling::Cs422, false),
+            (8, 7, ChromaSampling::Cs422, true),
+            (7, 8, ChromaSampling::Cs440, true),
+            (8, 7, ChromaSampling::Cs440, false),
+            (7, 7, ChromaSampling::Cs444, true),
+            (7, 7, ChromaSampling::Cs400, true),
+        ] {
+            let frame = Frame::<u8>::try_new_with_padding(width, height, chroma_sampling, 0, None);
+            if valid {
+                assert!(frame.is_ok());
+            } else {
+                assert_eq!(
+                    frame,
+                    Err(FrameError::InvalidDimensions {
+                        width,
+                        height,
+                        chroma_sampling
+                    })
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not compatible with")]
+    fn new_with_padding_asserts_dimensions() {
+        let _ = Frame::<u8>::new_with_padding(7, 7, ChromaSampling::Cs420, 0);
+    }
+
+    #[test]
+    fn visible_eq() {
+        let mut a = Frame::<u8>::new_with_padding(16, 8, ChromaSampling::Cs420, 0);
+        let mut b = Frame::<u8>::new_with_padding(16, 8, ChromaSampling::Cs420, 16);
+        a.fill(0);
+        b.fill(255);
+        let a = filled(a);
+        let mut b = filled(b);
+
+        assert!(a.visible_eq(&b));
+        assert_ne!(a, b);
+        assert_eq!(a, a.clone());
+
+        b.planes[2].data_origin_mut()[0] = 1;
+        assert!(!a.visible_eq(&b));
+
+        let c = Frame::<u8>::new(16, 8, ChromaSampling::Cs444);
+        assert!(!a.visible_eq(&c));
+    }
+
+    #[test]
+    fn content_hash() {
+        let mut a = Frame::<u16>::new_with_padding(16, 8, ChromaSampling::Cs420, 0);
+        let mut b = Frame::<u16>::new_with_padding(16, 8, ChromaSampling::Cs420, 16);
+        a.fill(0);
+        b.fill(1);
+        for frame in [&mut a, &mut b] {
+            for plane in frame.planes.iter_mut() {
+                for row in plane.rows_iter_mut() {
+                    row.fill(512);
+                }
+            }
+        }
+        assert!(a.visible_eq(&b));
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        b.planes[1].data_origin_mut()[0] = 511;
+        assert_ne!(a.content_hash(), b.content_hash());
+
+        let c = Frame::<u16>::new(16, 8, ChromaSampling::Cs422);
+        let d = Frame::<u16>::new(16, 8, ChromaSampling::Cs420);
+        assert_ne!(c.content_hash(), d.content_hash());
+    }
+
+    #[test]
+    fn max_value() {
+        assert_eq!(Frame::<u8>::max_value(8), 255);
+        assert_eq!(Frame::<u16>::max_value(8), 255);
+        assert_eq!(Frame::<u16>::max_value(10), 1023);
+        assert_eq!(Frame::<u16>::max_value(12), 4095);
+        assert_eq!(Frame::<u16>::max_value(16), 65535);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid bit depth")]
+    fn max_value_too_deep() {
+        Frame::<u8>::max_value(10);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let frame = filled(Frame::<u16>::new_with_padding(
+            16,
+            8,
+            ChromaSampling::Cs422,
+            8,
+        ));
+
+        let bytes = frame.to_bytes();
+        assert_eq!(bytes.len(), 10 + (16 * 8 + 2 * 8 * 8) * 2);
+        assert_eq!(&bytes[..10], &[16, 0, 0, 0, 8, 0, 0, 0, 1, 2]);
+
+        let decoded = Frame::<u16>::from_bytes(&bytes).unwrap();
+        assert!(decoded.visible_eq(&frame));
+        assert_eq!(decoded.planes[0].cfg.xpad, 0);
+
+        assert_eq!(
+            Frame::<u8>::from_bytes(&bytes),
+            Err(FrameError::InvalidData)
+        );
+        assert_eq!(
+            Frame::<u16>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(FrameError::InvalidData)
+        );
+        assert_eq!(
+            Frame::<u16>::from_bytes(&bytes[..9]),
+            Err(FrameError::InvalidData)
+        );
+
+        let mut bogus = bytes;
+        bogus[8] = 42;
+        assert_eq!(
+            Frame::<u16>::from_bytes(&bogus),
+            Err(FrameError::InvalidData)
+        );
+        bogus[8] = 1;
+        bogus[3] = 0xff;
+        assert_eq!(
+            Frame::<u16>::from_bytes(&bogus),
+            Err(FrameError::InvalidData)
+        );
+    }
+
+    #[test]
+    fn new_411() {
+        let frame = Frame::<u8>::new_with_padding(16, 8, ChromaSampling::Cs411, 8);
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs411);
+
+        for plane in &frame.planes[1..] {
+            assert_eq!(plane.cfg.width, 4);
+            assert_eq!(plane.cfg.height, 8);
+            assert_eq!((plane.cfg.xdec, plane.cfg.ydec), (2, 0));
+            assert_eq!((plane.cfg.xpad, plane.cfg.ypad), (2, 8));
+        }
+
+        assert_eq!(ChromaSampling::Cs411.get_chroma_dimensions(17, 3), (5, 3));
+    }
+
+    #[test]
+    fn map() {
+        use std::cell::Cell;
+
+        let mut frame = Frame::<u8>::new_with_padding(16, 8, ChromaSampling::Cs420, 8);
+        frame.fill(0);
+        for plane in frame.planes.iter_mut() {
+            for row in plane.rows_iter_mut() {
+                row.fill(10);
+            }
+        }
+
+        let calls = Cell::new(0);
+        let mapped = frame.map(|v| {
+            calls.set(calls.get() + 1);
+            v as u16 * 3
+        });
+
+        assert_eq!(calls.get(), 16 * 8 + 2 * 8 * 4);
+        assert_eq!(mapped.chroma_sampling(), ChromaSampling::Cs420);
+        for (a, b) in mapped.planes.iter().zip(frame.planes.iter()) {
+            assert_eq!(a.cfg.xpad, b.cfg.xpad);
+            assert_eq!(a.cfg.ypad, b.cfg.ypad);
+            assert!(a.data.iter().all(|&p| p == 30));
+        }
+    }
+
+    #[test]
+    fn clamp_to_bit_depth() {
+        let mut frame = Frame::<u16>::new_with_padding(16, 8, ChromaSampling::Cs420, 8);
+        frame.fill(0xffff);
+        frame.clamp_to_bit_depth(10);
+
+        for plane in frame.planes.iter() {
+            assert!(plane.iter().all(|p| p == 1023));
+        }
+        assert_eq!(frame.planes[0].data[0], 0xffff);
+    }
+
+    #[test]
+    fn compact_clone() {
+        let mut frame = Frame::<u16>::new_with_padding(16, 16, ChromaSampling::Cs420, 32);
+        frame.planes[0].set(3, 5, 1000);
+        frame.planes[2].set(7, 7, 7);
+
+        let compact = frame.compact_clone();
+        assert!(compact.visible_eq(&frame));
+        assert_eq!(compact.chroma_sampling(), ChromaSampling::Cs420);
+        for (compact, plane) in compact.planes.iter().zip(frame.planes.iter()) {
+            assert_eq!((compact.cfg.xpad, compact.cfg.ypad), (0, 0));
+            assert!(compact.data.len() < plane.data.len());
+        }
+    }
+
+    #[test]
+    fn tiles() {
+        let frame = filled(Frame::<u8>::new_unaligned(13, 9, ChromaSampling::Cs420));
+
+        let tiles = frame.tiles(3, 2);
+        assert_eq!(tiles.len(), 6);
+        let widths: Vec<_> = tiles[..3].iter().map(|t| t.planes[0].cfg.width).collect();
+        let heights: Vec<_> = tiles
+            .iter()
+            .step_by(3)
+            .map(|t| t.planes[0].cfg.height)
+            .collect();
+        assert_eq!(widths, [4, 4, 5]);
+        assert_eq!(heights, [4, 5]);
+        assert_eq!(tiles[5].planes[1].cfg.width, 3);
+        assert_eq!(tiles[5].planes[1].cfg.height, 3);
+
+        let mut reassembled = Frame::<u8>::new_unaligned(13, 9, ChromaSampling::Cs420);
+        reassembled.fill(0);
+        for (i, tile) in tiles.iter().enumerate() {
+            let x: usize = widths[..i % 3].iter().sum();
+            let y: usize = heights[..i / 3].iter().sum();
+            reassembled.blit(tile, x, y);
+        }
+        assert!(reassembled.visible_eq(&frame));
+    }
+
+    #[test]
+    fn from_tiles() {
+        let frame = filled(Frame::<u16>::new_unaligned(13, 9, ChromaSampling::Cs420));
+
+        let tiles = frame.tiles(3, 2);
+        let stitched = Frame::from_tiles(&tiles, 3, 2, 13, 9, ChromaSampling::Cs420);
+        assert!(stitched.visible_eq(&frame));
+    }
+
+    #[test]
+    #[should_panic(expected = "tile widths add up to 13, expected 14")]
+    fn from_tiles_width_mismatch() {
+        let frame = Frame::<u8>::new_unaligned(13, 9, ChromaSampling::Cs420);
+        let tiles = frame.tiles(3, 2);
+        Frame::from_tiles(&tiles, 3, 2, 14, 9, ChromaSampling::Cs420);
+    }
+
+    #[test]
+    #[should_panic(expected = "too small")]
+    fn tiles_too_small() {
+        let frame = Frame::<u8>::new_unaligned(4, 4, ChromaSampling::Cs420);
+        frame.tiles(3, 1);
+    }
+
+    #[test]
+    fn blit() {
+        let mut frame = Frame::<u8>::new_unaligned(8, 8, ChromaSampling::Cs420);
+        frame.fill(0);
+        let mut overlay = Frame::<u8>::new_unaligned(4, 4, ChromaSampling::Cs420);
+        overlay.fill(200);
+
+        frame.blit(&overlay, 6, 2);
+
+        for (y, row) in frame.planes[0].rows_iter().enumerate() {
+            let expected: &[u8] = if (2..6).contains(&y) {
+                &[0, 0, 0, 0, 0, 0, 200, 200]
+            } else {
+                &[0; 8]
+            };
+            assert_eq!(row, expected);
+        }
+        for plane in &frame.planes[1..] {
+            for (y, row) in plane.rows_iter().enumerate() {
+                let expected: &[u8] = if (1..3).contains(&y) {
+                    &[0, 0, 0, 200]
+                } else {
+                    &[0; 4]
+                };
+                assert_eq!(row, expected);
+            }
+        }
+
+        let before = frame.clone();
+        frame.blit(&overlay, 8, 0);
+        assert_eq!(frame, before);
+    }
+
+    #[test]
+    #[should_panic(expected = "same chroma sampling")]
+    fn blit_chroma_sampling_mismatch() {
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        let overlay = Frame::<u8>::new(4, 4, ChromaSampling::Cs444);
+        frame.blit(&overlay, 0, 0);
+    }
+
+    #[test]
+    fn blend() {
+        let mut frame = Frame::<u8>::new(4, 4, ChromaSampling::Cs420);
+        frame.fill(100);
+        let mut other = frame.clone();
+        other.fill(201);
+
+        let mut half = frame.clone();
+        half.blend(&other, 0.5);
+        assert!(half.planes.iter().all(|p| p.iter().all(|v| v == 151)));
+
+        let mut extrapolated = frame.clone();
+        extrapolated.blend(&other, 2.0);
+        assert!(extrapolated
+            .planes
+            .iter()
+            .all(|p| p.iter().all(|v| v == 255)));
+
+        frame.blend(&other, -1.0);
+        assert!(frame.planes.iter().all(|p| p.iter().all(|v| v == 0)));
+    }
+
+    #[test]
+    fn lerp() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.fill(100);
+        let mut next = frame.clone();
+        next.fill(300);
+
+        let midpoint = frame.lerp(&next, 0.5);
+        assert!(midpoint.planes.iter().all(|p| p.iter().all(|v| v == 200)));
+        assert!(frame.planes.iter().all(|p| p.iter().all(|v| v == 100)));
+        assert!(frame.lerp(&next, 1.0).visible_eq(&next));
+    }
+
+    #[test]
+    #[should_panic(expected = "plane dimensions do not match")]
+    fn blend_dimension_mismatch() {
+        let mut frame = Frame::<u8>::new(16, 16, ChromaSampling::Cs420);
+        let other = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        frame.blend(&other, 0.5);
+    }
+
+    #[test]
+    fn transpose() {
+        let frame = Frame::<u8>::new_unaligned(8, 4, ChromaSampling::Cs422);
+        let transposed = frame.transpose();
+
+        assert_eq!(transposed.chroma_sampling(), ChromaSampling::Cs440);
+        assert_eq!(
+            (
+                transposed.planes[0].cfg.width,
+                transposed.planes[0].cfg.height
+            ),
+            (4, 8)
+        );
+        assert_eq!(
+            (
+                transposed.planes[1].cfg.width,
+                transposed.planes[1].cfg.height
+            ),
+            (4, 4)
+        );
+        assert_eq!(
+            transposed.transpose().chroma_sampling(),
+            ChromaSampling::Cs422
+        );
+
+        let frame = Frame::<u8>::new_unaligned(8, 4, ChromaSampling::Cs420);
+        assert_eq!(frame.transpose().chroma_sampling(), ChromaSampling::Cs420);
+    }
+
+    #[test]
+    fn rotate() {
+        let mut frame = Frame::<u8>::new_unaligned(2, 2, ChromaSampling::Cs444);
+        for plane in &mut frame.planes {
+            for (y, row) in plane.rows_iter_mut().enumerate() {
+                row.copy_from_slice(&[1 + 2 * y as u8, 2 + 2 * y as u8]);
+            }
+        }
+        let luma = |frame: &Frame<u8>| -> Vec<Vec<u8>> {
+            frame.planes[0]
+                .rows_iter()
+                .map(|row| row.to_vec())
+                .collect()
+        };
+
+        assert_eq!(luma(&frame.rotate(Rotation::R90)), [[3, 1], [4, 2]]);
+        assert_eq!(luma(&frame.rotate(Rotation::R180)), [[4, 3], [2, 1]]);
+        assert_eq!(luma(&frame.rotate(Rotation::R270)), [[2, 4], [1, 3]]);
+
+        let frame = Frame::<u8>::new_unaligned(8, 4, ChromaSampling::Cs422);
+        let rotated = frame.rotate(Rotation::R90);
+        assert_eq!(rotated.chroma_sampling(), ChromaSampling::Cs440);
+        assert_eq!(
+            (rotated.planes[0].cfg.width, rotated.planes[0].cfg.height),
+            (4, 8)
+        );
+        let rotated = frame.rotate(Rotation::R180);
+        assert_eq!(rotated.chroma_sampling(), ChromaSampling::Cs422);
+        assert_eq!(
+            (rotated.planes[0].cfg.width, rotated.planes[0].cfg.height),
+            (8, 4)
+        );
+    }
+
+    #[test]
+    fn from_packed_422() {
+        let yuyv = [10, 100, 11, 200, 12, 101, 13, 201];
+        let frame = Frame::from_yuyv(&yuyv, 2, 2);
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs422);
+        let planes: Vec<Vec<&[u8]>> = frame
+            .planes
+            .iter()
+            .map(|p| p.rows_iter().collect())
+            .collect();
+        assert_eq!(planes[0], [[10, 11], [12, 13]]);
+        assert_eq!(planes[1], [[100], [101]]);
+        assert_eq!(planes[2], [[200], [201]]);
+
+        let uyvy = [100, 10, 200, 11, 101, 12, 201, 13];
+        assert_eq!(Frame::from_uyvy(&uyvy, 2, 2), frame);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong length")]
+    fn from_yuyv_wrong_length() {
+        Frame::from_yuyv(&[0; 6], 2, 2);
+    }
+
+    #[test]
+    fn nv12_round_trip() {
+        let y: Vec<u8> = (0..12).collect();
+        let uv = [100, 200, 101, 201, 102, 202, 103, 203];
+        let frame = Frame::from_nv12(&y, &uv, 3, 4);
+
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs420);
+        let cb: Vec<_> = frame.planes[1].rows_iter().collect();
+        let cr: Vec<_> = frame.planes[2].rows_iter().collect();
+        assert_eq!(cb, [[100, 101], [102, 103]]);
+        assert_eq!(cr, [[200, 201], [202, 203]]);
+        assert_eq!(frame.to_nv12(), (y, uv.to_vec()));
+    }
+
+    #[test]
+    fn chroma_plane_order() {
+        let mut frame = Frame::<u8>::new_unaligned(2, 2, ChromaSampling::Cs420);
+        frame.planes[0].fill(1);
+        frame.planes[1].fill(2);
+        frame.planes[2].fill(3);
+
+        assert_eq!(frame.to_planar(PlaneOrder::Yuv), [1, 1, 1, 1, 2, 3]);
+        assert_eq!(frame.to_planar(PlaneOrder::Yvu), [1, 1, 1, 1, 3, 2]);
+        let yv12 = frame.to_planar(PlaneOrder::Yvu);
+        let read = Frame::<u8>::read_planar_ordered(
+            &mut &yv12[..],
+            2,
+            2,
+            ChromaSampling::Cs420,
+            8,
+            PlaneOrder::Yvu,
+            Endianness::Little,
+        )
+        .unwrap();
+        assert!(read.visible_eq(&frame));
+
+        let (y, vu) = frame.to_nv21();
+        assert_eq!(vu, [3, 2]);
+        assert!(Frame::from_nv21(&y, &vu, 2, 2).visible_eq(&frame));
+        assert_eq!(frame.to_nv12().1, [2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "NV12 chroma data has the wrong length")]
+    fn from_nv12_wrong_length() {
+        Frame::from_nv12(&[0; 16], &[0; 6], 4, 4);
+    }
+
+    #[test]
+    fn empty_frame() {
+        let frame = Frame::<u16>::default();
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs420);
+        assert!(frame
+            .planes
+            .iter()
+            .all(|p| p.cfg.width == 0 && p.cfg.height == 0));
+        assert_eq!(frame.plane_stats(), [None, None, None]);
+        assert_eq!(frame.luma_histogram(10).iter().sum::<u32>(), 0);
+        assert!(format!("{:?}", Frame::<u8>::default()).contains("data: []"));
+
+        let mut out = Vec::new();
+        frame.write_planar(&mut out, Endianness::Little).unwrap();
+        assert!(out.is_empty());
+
+        let frame = Frame::<u8>::empty(ChromaSampling::Cs422);
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs422);
+        assert!(format!("{:?}", frame).contains("data: []"));
+        assert_eq!(Frame::from_bytes(&frame.to_bytes()), Ok(frame));
+    }
+
+    #[test]
+    fn to_420() {
+        let mut frame = Frame::<u8>::new_unaligned(3, 2, ChromaSampling::Cs444);
+        frame.planes[0].fill(7);
+        for (row, src) in frame.planes[1]
+            .rows_iter_mut()
+            .zip([[10, 20, 30], [11, 22, 40]])
+        {
+            row.copy_from_slice(&src);
+        }
+        frame.planes[2].fill(50);
+
+        let converted = frame.to_420();
+        assert_eq!(converted.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(converted.planes[0], frame.planes[0]);
+        let cb: Vec<_> = converted.planes[1].rows_iter().collect();
+        // (10 + 20 + 11 + 22) / 4 and (30 + 40) / 2, rounded
+        assert_eq!(cb, [[16, 35]]);
+        assert!(converted.planes[2].iter().all(|v| v == 50));
+
+        let mut frame = Frame::<u8>::new_unaligned(2, 3, ChromaSampling::Cs422);
+        for (row, src) in frame.planes[1].rows_iter_mut().zip([[1], [4], [9]]) {
+            row.copy_from_slice(&src);
+        }
+        let converted = frame.to_420();
+        assert_eq!(converted.chroma_sampling(), ChromaSampling::Cs420);
+        let cb: Vec<_> = converted.planes[1].rows_iter().collect();
+        assert_eq!(cb, [[3], [9]]);
+    }
+
+    #[test]
+    fn container_conversions() {
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        frame.planes[0].fill(255);
+        frame.planes[1].fill(3);
+
+        let wide = Frame::<u16>::from(frame.clone());
+        assert!(wide.planes[0].iter().all(|v| v == 255));
+        assert!(wide.planes[1].iter().all(|v| v == 3));
+        assert!(Frame::<u8>::try_from(wide.clone())
+            .unwrap()
+            .visible_eq(&frame));
+
+        let mut wide = wide;
+        wide.planes[2].fill(256);
+        assert_eq!(
+            Frame::<u8>::try_from(wide),
+            Err(FrameError::SampleOutOfRange)
+        );
+    }
+
+    #[test]
+    fn abs_diff() {
+        let mut a = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        let mut b = a.clone();
+        a.fill(10);
+        b.fill(250);
+        b.planes[0].set(1, 1, 4);
+
+        let diff = a.abs_diff(&b);
+        assert_eq!(diff.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(diff.planes[0][(1, 1)], 6);
+        assert_eq!(diff.planes[0][(0, 0)], 240);
+        assert!(diff.planes[2].iter().all(|v| v == 240));
+    }
+
+    #[test]
+    fn extend_padding() {
+        let mut frame = Frame::<u8>::new_with_padding(8, 8, ChromaSampling::Cs420, 8);
+        frame.fill(0);
+        frame.planes[0].set(0, 0, 10);
+        frame.planes[0].set(7, 7, 20);
+        frame.planes[1].set(3, 0, 30);
+
+        frame.extend_padding();
+
+        let luma = &frame.planes[0];
+        let origin = luma.cfg.yorigin * luma.cfg.stride + luma.cfg.xorigin;
+        assert_eq!(luma.data[0], 10);
+        assert_eq!(luma.data[origin - 1], 10);
+        assert_eq!(luma.data[luma.data.len() - 1], 20);
+        assert!(luma.probe_padding(8, 8));
+
+        let cb = &frame.planes[1];
+        assert_eq!(cb.data[cb.cfg.stride - 1], 30);
+        assert!(cb.probe_padding(8, 8));
+    }
+
+    #[test]
+    fn plane_accessors() {
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        frame.luma_mut().fill(1);
+        let (cb, cr) = frame.chroma_mut().unwrap();
+        cb.fill(2);
+        cr.fill(3);
+
+        assert!(frame.luma().iter().all(|v| v == 1));
+        let (cb, cr) = frame.chroma().unwrap();
+        assert!(cb.iter().all(|v| v == 2));
+        assert!(cr.iter().all(|v| v == 3));
+        assert_eq!(frame.planes[2], *cr);
+
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        assert!(frame.chroma().is_none());
+        assert!(frame.chroma_mut().is_none());
+    }
+
+    #[test]
+    fn from_planes() {
+        let planes = [
+            Plane::<u8>::new(5, 3, 0, 0, 4, 4),
+            Plane::new(3, 2, 1, 1, 2, 2),
+            Plane::new(3, 2, 1, 1, 0, 0),
+        ];
+        let frame = Frame::from_planes(planes.clone(), ChromaSampling::Cs420);
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs420);
+        assert_eq!(frame.planes, planes);
+
+        assert_eq!(
+            Frame::try_from_planes(planes, ChromaSampling::Cs422),
+            Err(FrameError::DimensionMismatch)
+        );
+        let planes = [
+            Plane::<u8>::new(5, 3, 0, 0, 0, 0),
+            Plane::new(3, 2, 1, 1, 0, 0),
+            Plane::new(2, 2, 1, 1, 0, 0),
+        ];
+        assert_eq!(
+            Frame::try_from_planes(planes, ChromaSampling::Cs420),
+            Err(FrameError::DimensionMismatch)
+        );
+
+        let planes = [
+            Plane::<u8>::new(5, 3, 0, 0, 0, 0),
+            Plane::new(0, 0, 0, 0, 0, 0),
+            Plane::new(0, 0, 0, 0, 0, 0),
+        ];
+        let frame = Frame::from_planes(planes, ChromaSampling::Cs400);
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs400);
+    }
+
+    #[test]
+    #[should_panic(expected = "frame dimensions do not match")]
+    fn from_planes_mismatch() {
+        let planes = [
+            Plane::<u8>::new(4, 4, 0, 0, 0, 0),
+            Plane::new(4, 4, 0, 0, 0, 0),
+            Plane::new(4, 4, 0, 0, 0, 0),
+        ];
+        Frame::from_planes(planes, ChromaSampling::Cs420);
+    }
+
+    #[test]
+    fn checksums() {
+        let frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        let mut padded = Frame::<u8>::new_with_padding(8, 8, ChromaSampling::Cs420, 16);
+        padded.fill(128);
+        padded.planes[0].data[0] = 0;
+
+        let checksums = frame.checksums();
+        assert_eq!(checksums.each_ref().map(Vec::len), [8, 4, 4]);
+        assert_eq!(checksums, padded.checksums());
+    }
+
+    #[test]
+    fn iter_planes() {
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        let planes: Vec<_> = frame
+            .iter_planes()
+            .map(|(pli, dec, plane)| {
+                assert_eq!(dec, (plane.cfg.xdec, plane.cfg.ydec));
+                (pli, dec)
+            })
+            .collect();
+        assert_eq!(planes, [(0, (0, 0)), (1, (1, 1)), (2, (1, 1))]);
+
+        for (pli, _, plane) in frame.iter_planes_mut() {
+            plane.fill(pli as u8);
+        }
+        assert!(frame.planes[2].iter().all(|v| v == 2));
+
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        assert_eq!(frame.iter_planes().count(), 1);
+        assert_eq!(
+            frame
+                .iter_planes_mut()
+                .map(|(pli, ..)| pli)
+                .collect::<Vec<_>>(),
+            [0]
+        );
+    }
+
+    #[test]
+    fn frame_sad() {
+        let mut a = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        let mut b = a.clone();
+        a.fill(10);
+        b.fill(12);
+        b.planes[0].set(0, 0, 0);
+
+        assert_eq!(a.frame_sad(&b), (63 + 2 * 16) * 2 + 10);
+        assert_eq!(a.frame_sad(&a), 0);
+    }
+
+    #[test]
+    fn offset_luma() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.planes[0].set(0, 0, 60);
+        frame.offset_luma(-64, 10);
+        assert_eq!(frame.planes[0].p(0, 0), 0);
+        assert_eq!(frame.planes[0].p(1, 0), 64);
+        assert!(frame.planes[1].iter().all(|p| p == 128));
+        frame.offset_luma(2000, 10);
+        assert!(frame.planes[0].iter().all(|p| p == 1023));
+    }
+
+    #[test]
+    fn apply_luma_lut() {
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        let gamma: Vec<u8> = (0..=255u8)
+            .map(|v| <u8 as Pixel>::from_f32((v as f32 / 255.0).powf(1.0 / 2.2) * 255.0))
+            .collect();
+        frame.apply_luma_lut(&gamma);
+        assert!(frame.planes[0].iter().all(|p| p == gamma[128]));
+        assert!(frame.planes[1].iter().all(|p| p == 128));
+    }
+
+    #[test]
+    fn write_planar_with_alpha() {
+        let mut frame = FrameWithAlpha::<u8>::new(8, 8, ChromaSampling::Cs420);
+        frame.alpha.fill(255);
+        assert_eq!(frame.alpha.cfg, frame.frame.planes[0].cfg);
+
+        let mut out = Vec::new();
+        frame.write_planar(&mut out, Endianness::Little).unwrap();
+        assert_eq!(out.len(), 64 + 16 + 16 + 64);
+        assert!(out[..96].iter().all(|&p| p == 128));
+        assert!(out[96..].iter().all(|&p| p == 255));
+
+        let mono = Frame::<u16>::new(8, 8, ChromaSampling::Cs400);
+        let alpha = Plane::new(8, 8, 0, 0, 0, 0);
+        let mut out = Vec::new();
+        FrameWithAlpha::from_parts(mono, alpha)
+            .write_planar(&mut out, Endianness::Little)
+            .unwrap();
+        assert_eq!(out.len(), (64 + 64) * 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the luma plane")]
+    fn alpha_plane_dimensions() {
+        let frame = Frame::<u8>::new(16, 16, ChromaSampling::Cs420);
+        FrameWithAlpha::from_parts(frame, Plane::new(8, 8, 0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_patterns() {
+        let gray =
+            Frame::<u8>::test_pattern(6, 4, ChromaSampling::Cs420, 8, TestPattern::SolidGray(90));
+        assert_eq!(gray.planes[0].cfg.width, 6);
+        assert!(gray.planes[0].iter().all(|p| p == 90));
+        assert!(gray.planes[1].iter().all(|p| p == 128));
+
+        let checkerboard = Frame::<u16>::test_pattern(
+            8,
+            4,
+            ChromaSampling::Cs444,
+            10,
+            TestPattern::Checkerboard { size: 2 },
+        );
+        let rows: Vec<&[u16]> = checkerboard.planes[0].rows_iter().collect();
+        assert_eq!(rows[0], [64, 64, 940, 940, 64, 64, 940, 940]);
+        assert_eq!(rows[2], [940, 940, 64, 64, 940, 940, 64, 64]);
+        assert!(checkerboard.planes[2].iter().all(|p| p == 512));
+
+        let ramp = Frame::<u16>::test_pattern(
+            5,
+            1,
+            ChromaSampling::Cs400,
+            12,
+            TestPattern::HorizontalRamp,
+        );
+        assert_eq!(
+            ramp.planes[0].rows_iter().next().unwrap(),
+            [0, 1023, 2047, 3071, 4095]
+        );
+
+        let bars =
+            Frame::<u8>::test_pattern(14, 2, ChromaSampling::Cs422, 8, TestPattern::ColorBars);
+        let luma: Vec<u8> = bars.planes[0]
+            .rows_iter()
+            .next()
+            .unwrap()
+            .iter()
+            .step_by(2)
+            .copied()
+            .collect();
+        assert_eq!(luma, [180, 162, 131, 112, 84, 65, 35]);
+        assert_eq!(
+            bars.planes[1].rows_iter().next().unwrap(),
+            [128, 44, 156, 72, 184, 100, 212]
+        );
+        assert_eq!(
+            bars.planes[2].rows_iter().next().unwrap(),
+            [128, 142, 44, 58, 198, 212, 114]
+        );
+    }
+
+    #[test]
+    fn hash_visible_content() {
+        use std::collections::HashSet;
+
+        let mut a = Frame::<u8>::new_with_padding(16, 8, ChromaSampling::Cs420, 0);
+        let mut b = Frame::<u8>::new_with_padding(16, 8, ChromaSampling::Cs420, 16);
+        a.fill(0);
+        b.fill(255);
+        for frame in [&mut a, &mut b] {
+            for plane in frame.planes.iter_mut() {
+                for row in plane.rows_iter_mut() {
+                    row.fill(1);
+                }
+            }
+            frame.planes[0].set(3, 2, 7);
+        }
+
+        let mut set = HashSet::new();
+        set.insert(a.clone());
+        assert!(set.contains(&a));
+        let hash = |frame: &Frame<u8>| {
+            let mut hasher = FxHasher::default();
+            frame.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(hash(&a), a.content_hash());
+
+        b.planes[0].set(3, 2, 8);
+        assert_ne!(hash(&a), hash(&b));
+        let c = Frame::<u8>::new(16, 8, ChromaSampling::Cs444);
+        assert_ne!(hash(&a), hash(&c));
+    }
+
+    #[test]
+    fn normalize_to_bit_depth() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        let values = [0, 1, 2, 128, 255, 300];
+        for (x, &v) in values.iter().enumerate() {
+            frame.planes[0].set(x, 0, v);
+        }
+
+        let ten_bit = frame.normalize_to_bit_depth(8, 10);
+        let row = &ten_bit.planes[0].rows_iter().next().unwrap()[..6];
+        assert_eq!(row, [0, 4, 8, 514, 1023, 1023]);
+        assert!(ten_bit.planes[1].iter().all(|p| p == 514));
+
+        let back = ten_bit.normalize_to_bit_depth(10, 8);
+        let row = &back.planes[0].rows_iter().next().unwrap()[..6];
+        assert_eq!(row, [0, 1, 2, 128, 255, 255]);
+
+        let twelve_bit = frame.normalize_to_bit_depth(10, 12);
+        assert_eq!(twelve_bit.planes[0].p(4, 0), 1021);
+        assert_eq!(frame.normalize_to_bit_depth(16, 8).planes[0].p(3, 0), 0);
+    }
+
+    #[test]
+    fn swap_frames() {
+        let mut a = Frame::<u16>::new(16, 8, ChromaSampling::Cs420);
+        let mut b = Frame::<u16>::new_with_padding(16, 8, ChromaSampling::Cs420, 8);
+        a.planes[0].fill(1);
+        b.planes[2].fill(2);
+
+        a.swap(&mut b);
+        assert_eq!(a.planes[0].cfg.xpad, 8);
+        assert!(a.planes[2].iter().all(|p| p == 2));
+        assert!(b.planes[0].iter().all(|p| p == 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "plane dimensions do not match")]
+    fn swap_frames_mismatch() {
+        let mut a = Frame::<u16>::new(16, 8, ChromaSampling::Cs420);
+        let mut b = Frame::<u16>::new(16, 8, ChromaSampling::Cs444);
+        a.swap(&mut b);
+    }
+
+    #[test]
+    fn chroma_key_mask() {
+        let mut frame = Frame::<u8>::new(16, 8, ChromaSampling::Cs420);
+        // a green 6x4 luma region at (2, 2), which covers chroma samples
+        // (1, 1) to (3, 2)
+        for y in 1..3 {
+            for x in 1..4 {
+                frame.planes[1].set(x, y, 54);
+                frame.planes[2].set(x, y, 34);
+            }
+        }
+        frame.planes[1].set(3, 2, 60);
+
+        let mask = frame.chroma_key_mask((54, 34), 8);
+        assert_eq!((mask.cfg.width, mask.cfg.height), (16, 8));
+        let rows: Vec<&[u8]> = mask.rows_iter().collect();
+        assert!(rows[1].iter().all(|&m| m == 0));
+        for row in &rows[2..6] {
+            assert_eq!(row[..2], [0, 0]);
+            assert!(row[2..8].iter().all(|&m| m == 255));
+            assert!(row[8..].iter().all(|&m| m == 0));
+        }
+        assert!(rows[6].iter().all(|&m| m == 0));
+
+        let mask = frame.chroma_key_mask((54, 34), 5);
+        assert_eq!(mask.p(6, 4), 0);
+        assert_eq!(mask.p(6, 3), 255);
+    }
+
+    #[test]
+    fn for_each_pixel_mut() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.for_each_pixel_mut(|pli, x, y, pixel| *pixel = (pli * 100 + y * 8 + x) as u16);
+
+        assert_eq!(frame.planes[0].p(7, 7), 63);
+        assert_eq!(frame.planes[1].p(3, 2), 119);
+        assert_eq!(frame.planes[2].p(3, 3), 227);
+        assert_eq!(frame.planes[2].iter().count(), 16);
+    }
+
+    #[test]
+    fn is_black() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        assert!(!frame.is_black(10));
+
+        frame.planes[0].fill(64);
+        frame.planes[1].fill(512);
+        frame.planes[2].fill(512);
+        assert!(frame.is_black(10));
+
+        frame.planes[0].set(2, 2, 0);
+        frame.planes[1].set(1, 1, 528);
+        assert!(frame.is_black(10));
+        frame.planes[1].set(1, 1, 529);
+        assert!(!frame.is_black(10));
+        frame.planes[1].set(1, 1, 512);
+        frame.planes[0].set(7, 7, 65);
+        assert!(!frame.is_black(10));
+
+        let mut mono = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        mono.planes[0].fill(16);
+        assert!(mono.is_black(8));
+    }
+
+    #[test]
+    fn pack_msb() {
+        let mut frame = Frame::<u16>::new(8, 8, ChromaSampling::Cs420);
+        frame.planes[0].set(0, 0, 1023);
+        frame.planes[0].set(1, 0, 1);
+
+        let packed = frame.pack_msb(10);
+        assert_eq!(packed.planes[0].p(0, 0), 0xffc0);
+        assert_eq!(packed.planes[0].p(1, 0), 0x40);
+        assert_eq!(packed.planes[1].p(0, 0), 128 << 6);
+        assert!(packed.unpack_msb(10).visible_eq(&frame));
+
+        frame.planes[0].set(2, 0, 4095);
+        let packed = frame.pack_msb(12);
+        assert_eq!(packed.planes[0].p(2, 0), 0xfff0);
+        assert_eq!(packed.planes[0].p(1, 0), 0x10);
+        assert!(packed.unpack_msb(12).visible_eq(&frame));
+    }
+
+    #[test]
+    fn to_packed_10bit() {
+        let mut frame = Frame::<u16>::new_unaligned(5, 1, ChromaSampling::Cs400);
+        for (x, v) in [0x3ff, 0x001, 0x200, 0x155, 0x0ff].into_iter().enumerate() {
+            frame.planes[0].set(x, 0, v);
+        }
+
+        let packed = frame.to_packed_10bit();
+        assert_eq!(packed.len(), 10);
+        let group = u64::from_le_bytes([
+            packed[0], packed[1], packed[2], packed[3], packed[4], 0, 0, 0,
+        ]);
+        assert_eq!(group, 0x3ff | 0x001 << 10 | 0x200 << 20 | 0x155 << 30);
+        assert_eq!(packed[5..], [0xff, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn approx_eq() {
+        let a = Frame::<u16>::new(16, 8, ChromaSampling::Cs420);
+        let mut b = Frame::<u16>::new_with_padding(16, 8, ChromaSampling::Cs420, 8);
+        assert!(a.approx_eq(&b, 0));
+
+        b.planes[0].set(3, 1, 130);
+        b.planes[2].set(5, 2, 125);
+        assert!(!a.approx_eq(&b, 1));
+        assert!(!b.approx_eq(&a, 2));
+        assert!(a.approx_eq(&b, 3));
+        assert_eq!(
+            a.approx_eq_report(&b, 1),
+            Ok(Some(SampleMismatch {
+                plane: 0,
+                x: 3,
+                y: 1,
+                left: 128,
+                right: 130
+            }))
+        );
+        assert_eq!(
+            a.approx_eq_report(&b, 2)
+                .unwrap()
+                .map(|m| (m.plane, m.x, m.y)),
+            Some((2, 5, 2))
+        );
+
+        let c = Frame::<u16>::new(16, 8, ChromaSampling::Cs444);
+        assert!(!a.approx_eq(&c, u32::MAX));
+        assert_eq!(
+            a.approx_eq_report(&c, 0),
+            Err(FrameError::DimensionMismatch)
+        );
+    }
+
+    #[test]
+    fn interlaced_fields() {
+        let mut frame = Frame::<u8>::new_unaligned(4, 4, ChromaSampling::Cs420);
+        frame.for_each_pixel_mut(|pli, x, y, pixel| *pixel = (pli * 100 + y * 10 + x) as u8);
+
+        let (top, bottom) = frame.split_fields();
+        assert_eq!(top.planes[0].cfg.height, 2);
+        assert_eq!(top.planes[1].cfg.height, 1);
+        assert_eq!(top.planes[0].rows_iter().nth(1).unwrap(), [20, 21, 22, 23]);
+        assert_eq!(
+            bottom.planes[0].rows_iter().nth(1).unwrap(),
+            [30, 31, 32, 33]
+        );
+        assert_eq!(top.planes[2].rows_iter().next().unwrap(), [200, 201]);
+        assert_eq!(bottom.planes[2].rows_iter().next().unwrap(), [210, 211]);
+
+        let woven = Frame::<u8>::from_interlaced_fields(
+            &top.to_planar(PlaneOrder::Yuv),
+            &bottom.to_planar(PlaneOrder::Yuv),
+            4,
+            4,
+            ChromaSampling::Cs420,
+        );
+        assert!(woven.visible_eq(&frame));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be split into fields")]
+    fn split_fields_odd_chroma() {
+        Frame::<u8>::new_unaligned(4, 6, ChromaSampling::Cs420).split_fields();
+    }
+
+    #[test]
+    fn memory_usage() {
+        let unpadded = Frame::<u8>::new(64, 64, ChromaSampling::Cs420);
+        let usage = unpadded.memory_usage();
+        assert_eq!(usage.visible_bytes, 64 * 64 + 2 * 32 * 32);
+        // chroma rows are aligned to 64 pixels
+        assert_eq!(usage.allocated_bytes, 64 * 64 + 2 * 64 * 32);
+        assert!((usage.padding_overhead() - 1.0 / 3.0).abs() < 1e-12);
+
+        let padded = Frame::<u8>::new_with_padding(64, 64, ChromaSampling::Cs420, 64);
+        let usage = padded.memory_usage();
+        assert_eq!(usage.visible_bytes, unpadded.memory_usage().visible_bytes);
+        assert!(usage.padding_overhead() > 4.0);
+        assert_eq!(
+            padded.compact_clone().memory_usage(),
+            unpadded.memory_usage()
+        );
+    }
+
+    #[test]
+    fn tagged_bytes() {
+        let mut frame = Frame::<u16>::new(16, 8, ChromaSampling::Cs422);
+        frame.planes[0].set(1, 2, 1023);
+
+        let bytes = frame.to_tagged_bytes();
+        assert_eq!(&bytes[..7], b"VFRM\x01\x0a\x00");
+        assert_eq!(bytes.len(), 7 + 10 + (16 * 8 + 2 * 8 * 8) * 2);
+        let decoded = Frame::<u16>::try_from(&bytes[..]).unwrap();
+        assert!(decoded.visible_eq(&frame));
+
+        // unknown header fields are skipped
+        let mut extended = bytes[..17].to_vec();
+        extended[5] = 13;
+        extended.extend_from_slice(&[1, 2, 3]);
+        extended.extend_from_slice(&bytes[17..]);
+        assert!(Frame::<u16>::try_from(&extended[..])
+            .unwrap()
+            .visible_eq(&frame));
+
+        assert_eq!(
+            Frame::<u8>::try_from(&bytes[..]),
+            Err(FrameError::InvalidData)
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            Frame::<u16>::try_from(&trailing[..]),
+            Err(FrameError::InvalidData)
+        );
+    }
+
+    #[test]
+    fn tagged_bytes_invalid() {
+        let bytes = Frame::<u8>::new(16, 8, ChromaSampling::Cs420).to_tagged_bytes();
+
+        for len in [0, 3, 6, 10, 16, 17, bytes.len() - 1] {
+            assert_eq!(
+                Frame::<u8>::try_from(&bytes[..len]),
+                Err(FrameError::Truncated),
+                "truncated to {} bytes",
+                len
+            );
+        }
+
+        let garbage: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(37)).collect();
+        assert_eq!(
+            Frame::<u8>::try_from(&garbage[..]),
+            Err(FrameError::UnsupportedFormat)
+        );
+        let mut version = bytes.clone();
+        version[4] = 2;
+        assert_eq!(
+            Frame::<u8>::try_from(&version[..]),
+            Err(FrameError::UnsupportedFormat)
+        );
+
+        // a huge frame is rejected without allocating it
+        let mut huge = bytes[..17].to_vec();
+        huge[7..15].copy_from_slice(&[0xff, 0xff, 0, 0, 0xff, 0xff, 0, 0]);
+        assert_eq!(Frame::<u8>::try_from(&huge[..]), Err(FrameError::Truncated));
+        huge[7..15].fill(0xff);
+        assert_eq!(
+            Frame::<u8>::try_from(&huge[..]),
+            Err(FrameError::InvalidData)
+        );
+        let mut sampling = bytes;
+        sampling[15] = 42;
+        assert_eq!(
+            Frame::<u8>::try_from(&sampling[..]),
+            Err(FrameError::InvalidData)
+        );
+    }
+
+    #[test]
+    fn to_444_bilinear() {
+        let mut frame = Frame::<u8>::new_unaligned(8, 4, ChromaSampling::Cs420);
+        frame.planes[1].for_each_pixel_mut(|x, _, pixel| *pixel = (x * 40) as u8);
+        frame.planes[2].for_each_pixel_mut(|_, y, pixel| *pixel = (y * 100) as u8);
+
+        let upsampled = frame.to_444_bilinear();
+        assert_eq!(upsampled.chroma_sampling(), ChromaSampling::Cs444);
+        assert!(upsampled.planes[0]
+            .rows_iter()
+            .eq(frame.planes[0].rows_iter()));
+        // horizontally co-sited: odd columns are interpolated, the last one
+        // is clamped
+        for row in upsampled.planes[1].rows_iter() {
+            assert_eq!(row, [0, 20, 40, 60, 80, 100, 120, 120]);
+        }
+        // vertically centered: the middle rows are a quarter and three
+        // quarters of the way
+        let column: Vec<u8> = upsampled.planes[2].rows_iter().map(|row| row[5]).collect();
+        assert_eq!(column, [0, 25, 75, 100]);
+
+        let nearest = frame.to_444();
+        assert_eq!(nearest.planes[1].rows_iter().next().unwrap()[..2], [0, 0]);
+    }
+
+    #[test]
+    fn to_luma_only() {
+        let mut frame = Frame::<u16>::new_with_padding(16, 8, ChromaSampling::Cs420, 8);
+        frame.planes[0].set(1, 1, 1000);
+        frame.planes[1].fill(3);
+
+        let mono = frame.to_luma_only();
+        assert_eq!(mono.chroma_sampling(), ChromaSampling::Cs400);
+        assert_eq!(mono.planes[0], frame.planes[0]);
+        assert_eq!(mono.planes[1].allocated_bytes(), 0);
+        assert_eq!(
+            mono.memory_usage().allocated_bytes,
+            frame.planes[0].allocated_bytes()
+        );
+
+        let mut out = Vec::new();
+        mono.write_planar(&mut out, Endianness::Little).unwrap();
+        assert_eq!(out.len(), 16 * 8 * 2);
+        let [luma, cb, cr] = mono.plane_stats();
+        assert_eq!(luma.unwrap().max, 1000);
+        assert!(cb.is_none() && cr.is_none());
+        assert!(mono.chroma().is_none());
+    }
+
+    #[test]
+    fn odd_luma_padding() {
+        for (chroma_sampling, xpad, ypad) in [
+            (ChromaSampling::Cs420, 5, 5),
+            (ChromaSampling::Cs422, 5, 9),
+            (ChromaSampling::Cs411, 3, 9),
+            (ChromaSampling::Cs444, 9, 9),
+        ] {
+            let frame = Frame::<u8>::new_with_padding(16, 16, chroma_sampling, 9);
+            let (luma, chroma) = (&frame.planes[0].cfg, &frame.planes[1].cfg);
+            assert_eq!((chroma.xpad, chroma.ypad), (xpad, ypad));
+            // the chroma borders cover the luma borders
+            assert!(chroma.xpad << chroma.xdec >= luma.xpad);
+            assert!(chroma.ypad << chroma.ydec >= luma.ypad);
+        }
+
+        assert_eq!(
+            Frame::<u8>::try_new_with_padding(16, 16, ChromaSampling::Cs420, usize::MAX, None),
+            Err(FrameError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn median_filter() {
+        let mut frame = Frame::<u8>::new(16, 16, ChromaSampling::Cs420);
+        frame.planes[0].set(7, 9, 255);
+        frame.planes[1].set(3, 3, 0);
+        frame.planes[2].set(3, 3, 0);
+
+        let filtered = frame.median_filter(2);
+        assert_eq!(filtered.chroma_sampling(), ChromaSampling::Cs420);
+        for plane in filtered.planes.iter() {
+            assert_eq!(plane.is_uniform(), Some(128));
+        }
+    }
+
+    #[test]
+    fn into_planar_vecs() {
+        let mut frame = Frame::<u16>::new_with_padding(16, 8, ChromaSampling::Cs420, 8);
+        frame.fill(7);
+        frame.for_each_pixel_mut(|pli, x, y, pixel| *pixel = (pli * 1000 + y * 16 + x) as u16);
+
+        let [y, u, v] = frame.clone().into_planar_vecs();
+        assert_eq!(y, (0..128).collect::<Vec<u16>>());
+        assert_eq!(u.len(), 32);
+        assert_eq!(u[8..10], [1016, 1017]);
+        assert_eq!(v[31], 2000 + 3 * 16 + 7);
+        assert_eq!(
+            frame.to_planar(PlaneOrder::Yuv).len(),
+            (y.len() + u.len() + v.len()) * 2
+        );
+
+        let [y, u, v] = Frame::<u8>::new(8, 8, ChromaSampling::Cs400).into_planar_vecs();
+        assert_eq!(y, [128; 64]);
+        assert!(u.is_empty() && v.is_empty());
+    }
+
+    #[test]
+    fn planar_endianness() {
+        let mut frame = Frame::<u16>::new_unaligned(2, 2, ChromaSampling::Cs420);
+        frame.planes[0].fill(0x0102);
+        frame.planes[1].fill(0x0304);
+        frame.planes[2].fill(0x0506);
+
+        let le = frame.to_bytes_with_endian(Endianness::Little);
+        let be = frame.to_bytes_with_endian(Endianness::Big);
+        assert_eq!(le, [2, 1, 2, 1, 2, 1, 2, 1, 4, 3, 6, 5]);
+        assert_eq!(be, [1, 2, 1, 2, 1, 2, 1, 2, 3, 4, 5, 6]);
+
+        for (endian, data) in [(Endianness::Little, le), (Endianness::Big, be)] {
+            let read = Frame::<u16>::read_planar_ordered(
+                &mut &data[..],
+                2,
+                2,
+                ChromaSampling::Cs420,
+                16,
+                PlaneOrder::Yuv,
+                endian,
+            )
+            .unwrap();
+            assert!(read.visible_eq(&frame));
+        }
+    }
+
+    #[test]
+    fn borrow_planar() {
+        let luma: Vec<u8> = (0..16).collect();
+        let cb = [50u8; 4];
+        let cr = [60u8, 61, 62, 63];
+        let frame = Frame::borrow_planar([&luma, &cb, &cr], 4, 4, ChromaSampling::Cs420);
+
+        assert_eq!(frame.chroma_sampling(), ChromaSampling::Cs420);
+        let rows: Vec<_> = frame.planes[0].rows_iter().collect();
+        assert_eq!(
+            rows,
+            [&luma[0..4], &luma[4..8], &luma[8..12], &luma[12..16]]
+        );
+        assert_eq!(frame.planes[2].row(1), [62, 63]);
+
+        let stats = frame.plane_stats();
+        assert_eq!(stats[0].map(|s| (s.min, s.max, s.mean)), Some((0, 15, 7.5)));
+        assert_eq!(stats[1].map(|s| s.mean), Some(50.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "too short")]
+    fn borrow_planar_short_slice() {
+        let luma = [0u8; 16];
+        let chroma = [0u8; 3];
+        Frame::borrow_planar([&luma, &chroma, &chroma], 4, 4, ChromaSampling::Cs420);
+    }
+
+    #[test]
+    fn plane_stats() {
+        let frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs400);
+        let stats = frame.plane_stats();
+
+        assert_eq!(
+            stats[0].map(|s| (s.min, s.max, s.mean)),
+            Some((128, 128, 128.0))
+        );
+        assert_eq!(stats[1], None);
+        assert_eq!(stats[2], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "too short")]
+    fn copy_from_raw_u8_short_source() {
+        let mut frame = Frame::<u8>::new(8, 8, ChromaSampling::Cs420);
+        let luma = vec![0u8; 63];
+        let chroma = vec![0u8; 16];
+
+        frame.copy_from_raw_u8([&luma, &chroma, &chroma], [8, 4, 4], 1);
+    }
 }
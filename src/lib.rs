@@ -7,6 +7,7 @@
 // Media Patent License 1.0 was not distributed with this source code in the
 // PATENTS file, you can obtain it at www.aomedia.org/license/patent.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(bare_trait_objects)]
 #![allow(clippy::cast_lossless)]
 #![allow(clippy::cast_ptr_alignment)]
@@ -37,10 +38,14 @@
 // FIXME: Temporarily disabled due to https://github.com/rust-lang/rust-clippy/issues/9142
 #![allow(clippy::undocumented_unsafe_blocks)]
 
+extern crate alloc;
+
+pub mod color;
 pub mod frame;
 pub mod math;
 pub mod pixel;
 pub mod plane;
+pub mod pool;
 
 mod serialize {
     cfg_if::cfg_if! {
@@ -7,14 +7,16 @@
 // Media Patent License 1.0 was not distributed with this source code in the
 // PATENTS file, you can obtain it at www.aomedia.org/license/patent.
 
+use core::mem::size_of;
 use num_traits::PrimInt;
-use std::mem::size_of;
 
 pub trait Fixed {
     fn floor_log2(&self, n: usize) -> usize;
     fn ceil_log2(&self, n: usize) -> usize;
     fn align_power_of_two(&self, n: usize) -> usize;
     fn align_power_of_two_and_shift(&self, n: usize) -> usize;
+    /// Like `align_power_of_two`, but returns `None` instead of overflowing.
+    fn align_power_of_two_checked(&self, n: usize) -> Option<usize>;
 }
 
 impl Fixed for usize {
@@ -34,6 +36,10 @@ impl Fixed for usize {
     fn align_power_of_two_and_shift(&self, n: usize) -> usize {
         (self + (1 << n) - 1) >> n
     }
+    #[inline]
+    fn align_power_of_two_checked(&self, n: usize) -> Option<usize> {
+        self.checked_add((1 << n) - 1).map(|v| v.floor_log2(n))
+    }
 }
 
 pub fn clamp<T: PartialOrd>(input: T, min: T, max: T) -> T {
@@ -69,3 +75,21 @@ pub fn msb(x: i32) -> i32 {
 pub const fn round_shift(value: i32, bit: usize) -> i32 {
     (value + (1 << bit >> 1)) >> bit
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn align_power_of_two_checked() {
+        assert_eq!(0usize.align_power_of_two_checked(3), Some(0));
+        assert_eq!(1usize.align_power_of_two_checked(3), Some(8));
+        assert_eq!(1919usize.align_power_of_two_checked(3), Some(1920));
+        assert_eq!(
+            (usize::MAX - 7).align_power_of_two_checked(3),
+            Some(usize::MAX - 7)
+        );
+        assert_eq!((usize::MAX - 6).align_power_of_two_checked(3), None);
+        assert_eq!(usize::MAX.align_power_of_two_checked(0), Some(usize::MAX));
+    }
+}
@@ -7,16 +7,22 @@
 // Media Patent License 1.0 was not distributed with this source code in the
 // PATENTS file, you can obtain it at www.aomedia.org/license/patent.
 
+// FIXME: `num_derive` 0.3 emits its impls inside an anonymous const, which
+// trips this lint on newer compilers.
+#![allow(non_local_definitions)]
+
 use crate::serialize::{Deserialize, Serialize};
 use crate::wasm_bindgen::*;
 
 use num_derive::FromPrimitive;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 use num_traits::{AsPrimitive, PrimInt, Signed};
 
-use std::fmt;
-use std::fmt::{Debug, Display};
-use std::mem::size_of;
-use std::ops::AddAssign;
+use core::fmt;
+use core::fmt::{Debug, Display};
+use core::mem::size_of;
+use core::ops::AddAssign;
 
 /// Trait for casting between primitive types.
 pub trait CastFromPrimitive<T>: Copy + 'static {
@@ -101,11 +107,64 @@ pub trait Pixel:
 {
     type Coeff: Coefficient;
 
+    /// The size of the type in bits.
+    const BITS: u32;
+
+    /// The largest value representable by the type.
+    const MAX_VALUE: Self;
+
     /// Returns a [`PixelType`] variant corresponding to this type.
     ///
     /// [`PixelType`]: enum.PixelType.html
     fn type_enum() -> PixelType;
 
+    /// Converts the pixel value to `f32`.
+    #[inline]
+    #[allow(clippy::wrong_self_convention)]
+    fn to_f32(self) -> f32 {
+        u32::cast_from(self) as f32
+    }
+
+    /// Converts the pixel value to `u32`.
+    #[inline]
+    #[allow(clippy::wrong_self_convention)]
+    fn to_u32(self) -> u32 {
+        self.into()
+    }
+
+    /// Converts a `u32` value to a pixel, clamping it to the range of the
+    /// type.
+    #[inline]
+    fn from_u32_clamped(v: u32) -> Self {
+        Self::cast_from(v.min(Self::MAX_VALUE.into()))
+    }
+
+    /// Converts an `f32` value to a pixel, rounding to the nearest integer
+    /// and clamping to the range of the type.
+    fn from_f32(v: f32) -> Self;
+
+    /// Adds `rhs` to the pixel value, clamping the result to the range of
+    /// `bit_depth` bits.
+    ///
+    /// Bit depths larger than the size of the type clamp to [`Self::MAX_VALUE`].
+    #[inline]
+    fn saturating_add_bd(self, rhs: i32, bit_depth: usize) -> Self {
+        let max = if bit_depth < Self::BITS as usize {
+            (1i32 << bit_depth) - 1
+        } else {
+            Self::MAX_VALUE.into()
+        };
+        let value: i32 = self.into();
+        <Self as CastFromPrimitive<i32>>::cast_from(value.saturating_add(rhs).clamp(0, max))
+    }
+
+    /// Subtracts `rhs` from the pixel value, clamping the result to the range
+    /// of `bit_depth` bits.
+    #[inline]
+    fn saturating_sub_bd(self, rhs: i32, bit_depth: usize) -> Self {
+        self.saturating_add_bd(rhs.saturating_neg(), bit_depth)
+    }
+
     /// Converts stride in pixels to stride in bytes.
     #[inline]
     #[allow(clippy::wrong_self_convention)]
@@ -117,19 +176,37 @@ pub trait Pixel:
 impl Pixel for u8 {
     type Coeff = i16;
 
+    const BITS: u32 = u8::BITS;
+    const MAX_VALUE: Self = u8::MAX;
+
     #[inline]
     fn type_enum() -> PixelType {
         PixelType::U8
     }
+
+    #[inline]
+    fn from_f32(v: f32) -> Self {
+        // float to int casts saturate and map NaN to 0
+        v.round() as u8
+    }
 }
 
 impl Pixel for u16 {
     type Coeff = i32;
 
+    const BITS: u32 = u16::BITS;
+    const MAX_VALUE: Self = u16::MAX;
+
     #[inline]
     fn type_enum() -> PixelType {
         PixelType::U16
     }
+
+    #[inline]
+    fn from_f32(v: f32) -> Self {
+        // float to int casts saturate and map NaN to 0
+        v.round() as u16
+    }
 }
 
 pub trait Coefficient:
@@ -145,12 +222,43 @@ impl Coefficient for i32 {
     type Pixel = u16;
 }
 
+/// Byte order of multi-byte samples in raw data.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl Endianness {
+    /// Returns the bytes of `value` in this byte order.
+    #[inline]
+    pub const fn u16_to_bytes(self, value: u16) -> [u8; 2] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+        }
+    }
+
+    /// Creates a `u16` from its bytes in this byte order.
+    #[inline]
+    pub const fn u16_from_bytes(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        }
+    }
+}
+
 /// Chroma subsampling format
 #[wasm_bindgen]
-#[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, FromPrimitive, Serialize, Deserialize)]
 #[repr(C)]
 pub enum ChromaSampling {
     /// Both vertically and horizontally subsampled.
+    #[default]
     Cs420,
     /// Horizontally subsampled.
     Cs422,
@@ -158,6 +266,10 @@ pub enum ChromaSampling {
     Cs444,
     /// Monochrome.
     Cs400,
+    /// Vertically subsampled.
+    Cs440,
+    /// Horizontally subsampled by 4.
+    Cs411,
 }
 
 impl fmt::Display for ChromaSampling {
@@ -170,21 +282,18 @@ impl fmt::Display for ChromaSampling {
                 ChromaSampling::Cs422 => "4:2:2",
                 ChromaSampling::Cs444 => "4:4:4",
                 ChromaSampling::Cs400 => "Monochrome",
+                ChromaSampling::Cs440 => "4:4:0",
+                ChromaSampling::Cs411 => "4:1:1",
             }
         )
     }
 }
 
-impl Default for ChromaSampling {
-    fn default() -> Self {
-        ChromaSampling::Cs420
-    }
-}
-
 impl ChromaSampling {
     /// Provides the amount to right shift the luma plane dimensions to get the
     ///  chroma plane dimensions.
-    /// Only values 0 or 1 are ever returned.
+    /// Only values 0 or 1 are ever returned, except for the horizontal
+    ///  decimation of Cs411 which is 2.
     /// The plane dimensions must also be rounded up to accommodate odd luma plane
     ///  sizes.
     /// Cs400 returns None, as there are no chroma planes.
@@ -195,7 +304,31 @@ impl ChromaSampling {
             Cs422 => Some((1, 0)),
             Cs444 => Some((0, 0)),
             Cs400 => None,
+            Cs440 => Some((0, 1)),
+            Cs411 => Some((2, 0)),
+        }
+    }
+
+    /// Rounds the given luma dimensions up to the smallest dimensions that are
+    /// multiples of 8, as allocated by `Frame::new_with_padding`, and
+    /// compatible with the chroma subsampling.
+    ///
+    /// Every supported subsampling divides 8, so the chroma sampling does not
+    /// currently change the result.
+    ///
+    /// # Panics
+    ///
+    /// - If a dimension is larger than `usize::MAX - 7`, so that it cannot be
+    ///   rounded up
+    pub const fn round_dimensions_up(self, width: usize, height: usize) -> (usize, usize) {
+        const fn round_up(v: usize) -> usize {
+            match v.checked_add(7) {
+                Some(v) => v & !7,
+                None => panic!("dimension is too large to be rounded up"),
+            }
         }
+
+        (round_up(width), round_up(height))
     }
 
     /// Calculates the size of a chroma plane for this sampling type, given the luma plane dimensions.
@@ -205,9 +338,133 @@ impl ChromaSampling {
         luma_height: usize,
     ) -> (usize, usize) {
         if let Some((ss_x, ss_y)) = self.get_decimation() {
-            ((luma_width + ss_x) >> ss_x, (luma_height + ss_y) >> ss_y)
+            (
+                (luma_width + (1 << ss_x) - 1) >> ss_x,
+                (luma_height + (1 << ss_y) - 1) >> ss_y,
+            )
         } else {
             (0, 0)
         }
     }
+
+    /// Calculates the `(width, height)` of the Y, U and V planes for this
+    /// sampling type, given the luma plane dimensions.
+    ///
+    /// The chroma planes of monochrome frames have a size of `(0, 0)`.
+    pub const fn plane_sizes(self, width: usize, height: usize) -> [(usize, usize); 3] {
+        let chroma = self.get_chroma_dimensions(width, height);
+        [(width, height), chroma, chroma]
+    }
+
+    /// Calculates the number of bytes of a frame with the given luma
+    /// dimensions, stored as tightly packed planes with `bytes_per_pixel`
+    /// bytes per sample.
+    pub const fn bytes_per_frame(
+        self,
+        width: usize,
+        height: usize,
+        bytes_per_pixel: usize,
+    ) -> usize {
+        let [(luma_width, luma_height), (chroma_width, chroma_height), _] =
+            self.plane_sizes(width, height);
+        (luma_width * luma_height + 2 * chroma_width * chroma_height) * bytes_per_pixel
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const _: () = assert!(<u8 as Pixel>::BITS == 8 && <u8 as Pixel>::MAX_VALUE == 255);
+    const _: () = assert!(<u16 as Pixel>::BITS == 16 && <u16 as Pixel>::MAX_VALUE == 65535);
+
+    #[test]
+    fn u32_conversions() {
+        assert_eq!(<u8 as Pixel>::to_u32(200), 200);
+        assert_eq!(<u16 as Pixel>::to_u32(1023), 1023);
+        assert_eq!(<u8 as Pixel>::from_u32_clamped(255), 255);
+        assert_eq!(<u8 as Pixel>::from_u32_clamped(256), 255);
+        assert_eq!(<u16 as Pixel>::from_u32_clamped(70000), 65535);
+    }
+
+    #[test]
+    fn round_dimensions_up() {
+        assert_eq!(ChromaSampling::Cs420.round_dimensions_up(1, 8), (8, 8));
+        assert_eq!(
+            ChromaSampling::Cs420.round_dimensions_up(1919, 1081),
+            (1920, 1088)
+        );
+        assert_eq!(ChromaSampling::Cs444.round_dimensions_up(0, 9), (0, 16));
+    }
+
+    #[test]
+    #[should_panic(expected = "dimension is too large")]
+    fn round_dimensions_up_overflow() {
+        ChromaSampling::Cs420.round_dimensions_up(8, usize::MAX - 6);
+    }
+
+    #[test]
+    fn plane_sizes() {
+        use ChromaSampling::*;
+
+        let expected = [
+            (Cs420, (960, 540), (2, 2)),
+            (Cs422, (960, 1080), (2, 3)),
+            (Cs444, (1920, 1080), (3, 3)),
+            (Cs440, (1920, 540), (3, 2)),
+            (Cs411, (480, 1080), (1, 3)),
+            (Cs400, (0, 0), (0, 0)),
+        ];
+        for (chroma_sampling, chroma, odd_chroma) in expected {
+            assert_eq!(
+                chroma_sampling.plane_sizes(1920, 1080),
+                [(1920, 1080), chroma, chroma]
+            );
+            assert_eq!(chroma_sampling.plane_sizes(3, 3)[1], odd_chroma);
+            assert_eq!(
+                chroma_sampling.bytes_per_frame(1920, 1080, 2),
+                (1920 * 1080 + 2 * chroma.0 * chroma.1) * 2
+            );
+        }
+        assert_eq!(Cs420.bytes_per_frame(1920, 1080, 1), 3_110_400);
+        assert_eq!(Cs400.bytes_per_frame(1920, 1080, 1), 2_073_600);
+        assert_eq!(Cs420.bytes_per_frame(3, 3, 1), 9 + 2 * 4);
+    }
+
+    #[test]
+    fn saturating_bit_depth_ops() {
+        assert_eq!(1000u16.saturating_add_bd(23, 10), 1023);
+        assert_eq!(1000u16.saturating_add_bd(24, 10), 1023);
+        assert_eq!(1000u16.saturating_add_bd(i32::MAX, 10), 1023);
+        assert_eq!(4000u16.saturating_add_bd(100, 12), 4095);
+        assert_eq!(65535u16.saturating_add_bd(1, 16), 65535);
+        assert_eq!(5u16.saturating_sub_bd(5, 10), 0);
+        assert_eq!(5u16.saturating_sub_bd(6, 10), 0);
+        assert_eq!(5u16.saturating_add_bd(-6, 10), 0);
+        assert_eq!(5u16.saturating_sub_bd(i32::MIN, 10), 1023);
+        assert_eq!(250u8.saturating_add_bd(10, 8), 255);
+        assert_eq!(250u8.saturating_sub_bd(10, 8), 240);
+    }
+
+    #[test]
+    fn saturating_bit_depth_ops_beyond_type_size() {
+        assert_eq!(250u8.saturating_add_bd(10, 10), 255);
+        assert_eq!(200u8.saturating_add_bd(100, 10), 255);
+        assert_eq!(200u8.saturating_add_bd(50, 32), 250);
+        assert_eq!(200u8.saturating_sub_bd(250, 64), 0);
+        assert_eq!(65000u16.saturating_add_bd(1000, 32), 65535);
+    }
+
+    #[test]
+    fn f32_conversion() {
+        assert_eq!(200u8.to_f32(), 200.0);
+        assert_eq!(1023u16.to_f32(), 1023.0);
+
+        assert_eq!(u8::from_f32(127.5), 128);
+        assert_eq!(u8::from_f32(-3.0), 0);
+        assert_eq!(u8::from_f32(300.0), 255);
+        assert_eq!(u16::from_f32(1022.4), 1022);
+        assert_eq!(u16::from_f32(70000.0), 65535);
+        assert_eq!(u16::from_f32(f32::NAN), 0);
+    }
 }
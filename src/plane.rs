@@ -7,20 +7,19 @@
 // Media Patent License 1.0 was not distributed with this source code in the
 // PATENTS file, you can obtain it at www.aomedia.org/license/patent.
 
+use alloc::alloc::{alloc, dealloc, Layout};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Index, IndexMut, Range};
+use core::{iter::FusedIterator, ops::DerefMut};
 use debug_unreachable::debug_unreachable;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+#[cfg(feature = "tracing")]
 use rust_hawktracer::*;
-use std::marker::PhantomData;
-use std::mem;
-use std::ops::{Index, IndexMut, Range};
-use std::{
-    alloc::{alloc, dealloc, Layout},
-    u32,
-};
-use std::{
-    fmt::{Debug, Display, Formatter},
-    usize,
-};
-use std::{iter::FusedIterator, ops::DerefMut};
 
 use crate::math::*;
 use crate::pixel::*;
@@ -59,6 +58,9 @@ impl PlaneConfig {
     /// Stride alignment in bytes.
     const STRIDE_ALIGNMENT_LOG2: usize = 6;
 
+    /// # Panics
+    ///
+    /// - If the allocation size of the plane overflows `usize`
     #[inline]
     pub fn new(
         width: usize,
@@ -69,13 +71,32 @@ impl PlaneConfig {
         ypad: usize,
         type_size: usize,
     ) -> Self {
-        let xorigin = xpad.align_power_of_two(Self::STRIDE_ALIGNMENT_LOG2 + 1 - type_size);
-        let yorigin = ypad;
-        let stride = (xorigin + width + xpad)
-            .align_power_of_two(Self::STRIDE_ALIGNMENT_LOG2 + 1 - type_size);
-        let alloc_height = yorigin + height + ypad;
+        Self::try_new(width, height, xdec, ydec, xpad, ypad, type_size)
+            .expect("plane dimensions are too large")
+    }
 
-        PlaneConfig {
+    /// Returns `None` if the allocation size of the plane overflows `usize`.
+    #[inline]
+    pub fn try_new(
+        width: usize,
+        height: usize,
+        xdec: usize,
+        ydec: usize,
+        xpad: usize,
+        ypad: usize,
+        type_size: usize,
+    ) -> Option<Self> {
+        let align = Self::STRIDE_ALIGNMENT_LOG2 + 1 - type_size;
+        let xorigin = xpad.align_power_of_two_checked(align)?;
+        let yorigin = ypad;
+        let stride = xorigin
+            .checked_add(width)?
+            .checked_add(xpad)?
+            .align_power_of_two_checked(align)?;
+        let alloc_height = yorigin.checked_add(height)?.checked_add(ypad)?;
+        stride.checked_mul(alloc_height)?.checked_mul(type_size)?;
+
+        Some(PlaneConfig {
             stride,
             alloc_height,
             width,
@@ -86,10 +107,21 @@ impl PlaneConfig {
             ypad,
             xorigin,
             yorigin,
-        }
+        })
     }
 }
 
+/// Statistics over the visible pixels of a plane.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlaneStats<T: Pixel> {
+    /// Smallest pixel value.
+    pub min: T,
+    /// Largest pixel value.
+    pub max: T,
+    /// Arithmetic mean of the pixel values.
+    pub mean: f64,
+}
+
 /// Absolute offset in pixels inside a plane
 #[derive(Clone, Copy, Debug, Default)]
 pub struct PlaneOffset {
@@ -101,10 +133,10 @@ pub struct PlaneOffset {
 ///
 /// The buffer is padded and aligned according to the architecture-specific
 /// SIMD constraints.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug)]
 #[cfg_attr(not(feature = "serialize"), derive(Serialize, Deserialize))]
 pub struct PlaneData<T: Pixel> {
-    ptr: std::ptr::NonNull<T>,
+    ptr: core::ptr::NonNull<T>,
     _marker: PhantomData<T>,
     len: usize,
 }
@@ -123,7 +155,15 @@ impl<T: Pixel> Clone for PlaneData<T> {
     }
 }
 
-impl<T: Pixel> std::ops::Deref for PlaneData<T> {
+impl<T: Pixel> PartialEq for PlaneData<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Pixel> Eq for PlaneData<T> {}
+
+impl<T: Pixel> core::ops::Deref for PlaneData<T> {
     type Target = [T];
 
     fn deref(&self) -> &[T] {
@@ -131,24 +171,27 @@ impl<T: Pixel> std::ops::Deref for PlaneData<T> {
         unsafe {
             let p = self.ptr.as_ptr();
 
-            std::slice::from_raw_parts(p, self.len)
+            core::slice::from_raw_parts(p, self.len)
         }
     }
 }
 
-impl<T: Pixel> std::ops::DerefMut for PlaneData<T> {
+impl<T: Pixel> core::ops::DerefMut for PlaneData<T> {
     fn deref_mut(&mut self) -> &mut [T] {
         // SAFETY: we cannot reference out of bounds because we know the length of the data
         unsafe {
             let p = self.ptr.as_ptr();
 
-            std::slice::from_raw_parts_mut(p, self.len)
+            core::slice::from_raw_parts_mut(p, self.len)
         }
     }
 }
 
-impl<T: Pixel> std::ops::Drop for PlaneData<T> {
+impl<T: Pixel> core::ops::Drop for PlaneData<T> {
     fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
         // SAFETY: we cannot dealloc too much because we know the length of the data
         unsafe {
             dealloc(self.ptr.as_ptr() as *mut u8, Self::layout(self.len));
@@ -162,8 +205,8 @@ impl<T: Pixel + Serialize> Serialize for PlaneData<T> {
     where
         S: serde::Serializer,
     {
+        use core::ops::Deref;
         use serde::ser::SerializeSeq;
-        use std::ops::Deref;
 
         let mut data = serializer.serialize_seq(Some(self.len))?;
         for byte in self.deref() {
@@ -200,27 +243,44 @@ impl<T: Pixel> PlaneData<T> {
     }
 
     unsafe fn new_uninitialized(len: usize) -> Self {
-        let ptr = {
-            let ptr = alloc(Self::layout(len)) as *mut T;
-            std::ptr::NonNull::new_unchecked(ptr)
+        Self::try_new_uninitialized(len).expect("plane allocation failed")
+    }
+
+    /// Returns `None` if the allocation fails.
+    unsafe fn try_new_uninitialized(len: usize) -> Option<Self> {
+        let ptr = if len == 0 {
+            // Zero-sized allocations are not allowed, and are never freed
+            core::ptr::NonNull::dangling()
+        } else {
+            let size = len.checked_mul(mem::size_of::<T>())?;
+            let layout = Layout::from_size_align(size, 1 << Self::DATA_ALIGNMENT_LOG2).ok()?;
+            core::ptr::NonNull::new(alloc(layout) as *mut T)?
         };
 
-        PlaneData {
+        Some(PlaneData {
             ptr,
             len,
             _marker: PhantomData,
-        }
+        })
     }
 
+    /// # Panics
+    ///
+    /// - If the allocation fails
     pub fn new(len: usize) -> Self {
+        Self::try_new(len).expect("plane allocation failed")
+    }
+
+    /// Returns `None` if the allocation fails.
+    pub fn try_new(len: usize) -> Option<Self> {
         // SAFETY: we initialize the plane data before returning
-        let mut pd = unsafe { Self::new_uninitialized(len) };
+        let mut pd = unsafe { Self::try_new_uninitialized(len)? };
 
         for v in pd.iter_mut() {
             *v = T::cast_from(128);
         }
 
-        pd
+        Some(pd)
     }
 
     fn from_slice(data: &[T]) -> Self {
@@ -236,7 +296,11 @@ impl<T: Pixel> PlaneData<T> {
 /// One data plane of a frame.
 ///
 /// For example, a plane can be a Y luma plane or a U or V chroma plane.
-#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// With the `serialize` feature, only the dimensions, the decimation and the
+/// visible pixels are serialized. Deserialized planes have no padding.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(not(feature = "serialize"), derive(Serialize, Deserialize))]
 pub struct Plane<T: Pixel> {
     // TODO: it is used by encoder to copy by plane and by tiling, make it
     // private again once tiling is moved and a copy_plane fn is added.
@@ -246,21 +310,98 @@ pub struct Plane<T: Pixel> {
     pub cfg: PlaneConfig,
 }
 
+#[cfg(feature = "serialize")]
+mod serialize_impl {
+    use super::Plane;
+    use crate::pixel::Pixel;
+    use serde::de::Error;
+    use serde::ser::{SerializeSeq, SerializeStruct};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The visible pixels of a plane, serialized as a flat sequence.
+    struct VisiblePixels<'a, T: Pixel>(&'a Plane<T>);
+
+    impl<T: Pixel + Serialize> Serialize for VisiblePixels<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let plane = self.0;
+            let mut seq = serializer.serialize_seq(Some(plane.cfg.width * plane.cfg.height))?;
+            for pixel in plane.iter() {
+                seq.serialize_element(&pixel)?;
+            }
+            seq.end()
+        }
+    }
+
+    impl<T: Pixel + Serialize> Serialize for Plane<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut plane = serializer.serialize_struct("Plane", 5)?;
+            plane.serialize_field("width", &self.cfg.width)?;
+            plane.serialize_field("height", &self.cfg.height)?;
+            plane.serialize_field("xdec", &self.cfg.xdec)?;
+            plane.serialize_field("ydec", &self.cfg.ydec)?;
+            plane.serialize_field("data", &VisiblePixels(self))?;
+            plane.end()
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "Plane")]
+    struct SerializedPlane<T> {
+        width: usize,
+        height: usize,
+        xdec: usize,
+        ydec: usize,
+        data: Vec<T>,
+    }
+
+    impl<'de, T: Pixel + Deserialize<'de>> Deserialize<'de> for Plane<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let SerializedPlane {
+                width,
+                height,
+                xdec,
+                ydec,
+                data,
+            } = SerializedPlane::deserialize(deserializer)?;
+            if width.checked_mul(height) != Some(data.len()) {
+                return Err(D::Error::custom(format!(
+                    "{} pixels do not match the plane dimensions {}x{}",
+                    data.len(),
+                    width,
+                    height
+                )));
+            }
+
+            let mut plane = Plane::try_new(width, height, xdec, ydec, 0, 0)
+                .ok_or_else(|| D::Error::custom("plane is too large"))?;
+            if width > 0 {
+                for (row, src) in plane.rows_iter_mut().zip(data.chunks_exact(width)) {
+                    row.copy_from_slice(src);
+                }
+            }
+            Ok(plane)
+        }
+    }
+}
+
 impl<T: Pixel> Debug for Plane<T>
 where
     T: Display,
 {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Plane {{ data: [{}, ...], cfg: {:?} }}",
-            self.data[0], self.cfg
-        )
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.data.first() {
+            Some(first) => write!(f, "Plane {{ data: [{}, ...], cfg: {:?} }}", first, self.cfg),
+            None => write!(f, "Plane {{ data: [], cfg: {:?} }}", self.cfg),
+        }
     }
 }
 
 impl<T: Pixel> Plane<T> {
     /// Allocates and returns a new plane.
+    ///
+    /// # Panics
+    ///
+    /// - If the plane is too large or the allocation fails
     pub fn new(
         width: usize,
         height: usize,
@@ -275,6 +416,23 @@ impl<T: Pixel> Plane<T> {
         Plane { data, cfg }
     }
 
+    /// Allocates and returns a new plane.
+    ///
+    /// Returns `None` if the plane is too large or the allocation fails.
+    pub fn try_new(
+        width: usize,
+        height: usize,
+        xdec: usize,
+        ydec: usize,
+        xpad: usize,
+        ypad: usize,
+    ) -> Option<Self> {
+        let cfg = PlaneConfig::try_new(width, height, xdec, ydec, xpad, ypad, mem::size_of::<T>())?;
+        let data = PlaneData::try_new(cfg.stride * cfg.alloc_height)?;
+
+        Some(Plane { data, cfg })
+    }
+
     /// Allocates and returns an uninitialized plane.
     unsafe fn new_uninitialized(
         width: usize,
@@ -296,7 +454,7 @@ impl<T: Pixel> Plane<T> {
     pub fn from_slice(data: &[T], stride: usize) -> Self {
         let len = data.len();
 
-        assert!(len % stride == 0);
+        assert!(len.is_multiple_of(stride));
 
         Self {
             data: PlaneData::from_slice(data),
@@ -320,8 +478,8 @@ impl<T: Pixel> Plane<T> {
         let yorigin = self.cfg.yorigin;
         let stride = self.cfg.stride;
         let alloc_height = self.cfg.alloc_height;
-        let width = (w + self.cfg.xdec) >> self.cfg.xdec;
-        let height = (h + self.cfg.ydec) >> self.cfg.ydec;
+        let width = (w + (1 << self.cfg.xdec) - 1) >> self.cfg.xdec;
+        let height = (h + (1 << self.cfg.ydec) - 1) >> self.cfg.ydec;
 
         if xorigin > 0 {
             for y in 0..height {
@@ -362,6 +520,20 @@ impl<T: Pixel> Plane<T> {
         }
     }
 
+    /// Fills the padding by replicating the edge pixels of the visible area,
+    /// as given by the plane configuration.
+    ///
+    /// Empty planes are left untouched.
+    pub fn extend_padding(&mut self) {
+        if self.cfg.width == 0 || self.cfg.height == 0 {
+            return;
+        }
+        self.pad(
+            self.cfg.width << self.cfg.xdec,
+            self.cfg.height << self.cfg.ydec,
+        );
+    }
+
     /// Minimally test that the plane has been padded.
     pub fn probe_padding(&self, w: usize, h: usize) -> bool {
         let PlaneConfig {
@@ -373,8 +545,8 @@ impl<T: Pixel> Plane<T> {
             ydec,
             ..
         } = self.cfg;
-        let width = (w + xdec) >> xdec;
-        let height = (h + ydec) >> ydec;
+        let width = (w + (1 << xdec) - 1) >> xdec;
+        let height = (h + (1 << ydec) - 1) >> ydec;
         let corner = (yorigin + height - 1) * stride + xorigin + width - 1;
         let corner_value = self.data[corner];
 
@@ -433,7 +605,63 @@ impl<T: Pixel> Plane<T> {
         self.data[self.index(x, y)]
     }
 
+    /// Returns the pixel at the given visible coordinates, or `None` if they
+    /// are outside the visible area.
+    ///
+    /// This is a convenience for tests and tools; inner loops should iterate
+    /// over rows instead.
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        if x < self.cfg.width && y < self.cfg.height {
+            Some(self.p(x, y))
+        } else {
+            None
+        }
+    }
+
+    /// Sets the pixel at the given visible coordinates.
+    ///
+    /// This is a convenience for tests and tools; inner loops should iterate
+    /// over rows instead.
+    ///
+    /// # Panics
+    ///
+    /// - If the coordinates are outside the visible area
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        assert!(
+            x < self.cfg.width && y < self.cfg.height,
+            "pixel ({}, {}) is outside the plane dimensions {}x{}",
+            x,
+            y,
+            self.cfg.width,
+            self.cfg.height
+        );
+        let index = self.index(x, y);
+        self.data[index] = value;
+    }
+
+    /// Distance between the start of two rows, in pixels.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.cfg.stride
+    }
+
+    /// Visible width in pixels.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.cfg.width
+    }
+
+    /// Visible height in pixels.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.cfg.height
+    }
+
     /// Returns plane data starting from the origin.
+    ///
+    /// The slice starts at the first visible pixel and extends to the end of
+    /// the allocation, so it includes the right padding of every row and the
+    /// bottom padding. Row `y` starts at `y * stride()`.
     pub fn data_origin(&self) -> &[T] {
         &self.data[self.index(0, 0)..]
     }
@@ -444,6 +672,206 @@ impl<T: Pixel> Plane<T> {
         &mut self.data[i..]
     }
 
+    /// Returns the size of the allocated data of the plane in bytes,
+    /// including the padding and the stride alignment.
+    pub fn allocated_bytes(&self) -> usize {
+        self.cfg.stride * self.cfg.alloc_height * mem::size_of::<T>()
+    }
+
+    /// Returns the size of the visible pixels of the plane in bytes.
+    pub fn visible_bytes(&self) -> usize {
+        self.cfg.width * self.cfg.height * mem::size_of::<T>()
+    }
+
+    /// Sets every allocated pixel of the plane to `value`.
+    ///
+    /// This includes the padding, which makes the plane contents fully
+    /// deterministic.
+    pub fn fill(&mut self, value: T) {
+        self.data.fill(value);
+    }
+
+    /// Sets every allocated pixel of the plane, including the padding, to 0.
+    pub fn clear(&mut self) {
+        self.fill(T::cast_from(0));
+    }
+
+    /// Adds `delta` to every visible pixel, clamping the results to the range
+    /// of `bit_depth` bits.
+    ///
+    /// A negative `delta` can be used to subtract a black level. Bit depths
+    /// larger than the size of `T` clamp to [`Pixel::MAX_VALUE`].
+    pub fn offset(&mut self, delta: i32, bit_depth: usize) {
+        for row in self.rows_iter_mut() {
+            for pixel in row {
+                *pixel = pixel.saturating_add_bd(delta, bit_depth);
+            }
+        }
+    }
+
+    /// Returns the value of the visible pixels if they are all equal, or
+    /// `None` if they differ or the plane is empty.
+    ///
+    /// The scan stops at the first pixel that differs from the first one.
+    pub fn is_uniform(&self) -> Option<T> {
+        let first = *self.rows_iter().next()?.first()?;
+        self.rows_iter()
+            .all(|row| row.iter().all(|&p| p == first))
+            .then_some(first)
+    }
+
+    /// Calls `f` with the visible coordinates `(x, y)` and a mutable
+    /// reference to every visible pixel, in row-major order.
+    pub fn for_each_pixel_mut(&mut self, mut f: impl FnMut(usize, usize, &mut T)) {
+        for (y, row) in self.rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                f(x, y, pixel);
+            }
+        }
+    }
+
+    /// Maps every visible pixel through the lookup table `lut`, so that a
+    /// pixel with value `v` becomes `lut[v]`.
+    ///
+    /// The table must have `1 << bit_depth` entries, one for every value of
+    /// the bit depth of the plane.
+    ///
+    /// # Panics
+    ///
+    /// - If the length of `lut` is not a power of two that fits the pixel type
+    /// - If a pixel value does not fit in the bit depth implied by `lut`
+    pub fn apply_lut(&mut self, lut: &[T]) {
+        assert!(
+            lut.len().is_power_of_two() && lut.len() <= 1 << T::BITS,
+            "invalid lookup table length ({}) for a {}-bit pixel",
+            lut.len(),
+            T::BITS
+        );
+
+        for row in self.rows_iter_mut() {
+            for pixel in row {
+                let value = u32::cast_from(*pixel) as usize;
+                assert!(
+                    value < lut.len(),
+                    "pixel value {} is outside of the lookup table ({} entries)",
+                    value,
+                    lut.len()
+                );
+                *pixel = lut[value];
+            }
+        }
+    }
+
+    /// Swaps the contents of the plane with `other`, without copying the
+    /// pixels.
+    ///
+    /// The storage and configuration are exchanged as a whole, so the planes
+    /// may have a different stride or padding.
+    ///
+    /// # Panics
+    ///
+    /// - If the visible dimensions or decimation of the planes do not match
+    pub fn swap(&mut self, other: &mut Plane<T>) {
+        self.assert_same_geometry(other);
+        mem::swap(self, other);
+    }
+
+    pub(crate) fn assert_same_geometry(&self, other: &Plane<T>) {
+        assert!(
+            self.cfg.width == other.cfg.width
+                && self.cfg.height == other.cfg.height
+                && self.cfg.xdec == other.cfg.xdec
+                && self.cfg.ydec == other.cfg.ydec,
+            "plane dimensions do not match ({}x{} != {}x{})",
+            self.cfg.width,
+            self.cfg.height,
+            other.cfg.width,
+            other.cfg.height
+        );
+    }
+
+    /// Copies the visible pixels of `src` into the plane.
+    ///
+    /// When both planes share the same stride and horizontal origin the data
+    /// is copied as a single contiguous block, which also copies the right
+    /// padding of all but the last row. Otherwise it is copied row by row.
+    ///
+    /// # Panics
+    ///
+    /// - If the visible dimensions of the planes do not match
+    pub fn copy_from(&mut self, src: &Plane<T>) {
+        let PlaneConfig {
+            stride,
+            width,
+            height,
+            xorigin,
+            ..
+        } = self.cfg;
+        assert!(
+            width == src.cfg.width && height == src.cfg.height,
+            "plane dimensions do not match ({}x{} != {}x{})",
+            width,
+            height,
+            src.cfg.width,
+            src.cfg.height
+        );
+
+        if height == 0 {
+            return;
+        }
+
+        if stride == src.cfg.stride && xorigin == src.cfg.xorigin {
+            let len = (height - 1) * stride + width;
+            self.data_origin_mut()[..len].copy_from_slice(&src.data_origin()[..len]);
+        } else {
+            for (dst_row, src_row) in self.rows_iter_mut().zip(src.rows_iter()) {
+                dst_row.copy_from_slice(src_row);
+            }
+        }
+    }
+
+    /// Copies the visible rows into `dst`, starting each row `dst_stride`
+    /// bytes after the previous one.
+    ///
+    /// High bit depth samples are written in little-endian order. The bytes
+    /// between the end of a row and the start of the next are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// - If `dst_stride` is smaller than a row of visible pixels
+    /// - If `dst` is shorter than `height * dst_stride` bytes
+    pub fn copy_to_aligned(&self, dst: &mut [u8], dst_stride: usize) {
+        let row_bytes = self.cfg.width * mem::size_of::<T>();
+        assert!(
+            dst_stride >= row_bytes,
+            "destination stride ({}) is smaller than a row ({})",
+            dst_stride,
+            row_bytes
+        );
+        assert!(
+            dst.len() >= self.cfg.height * dst_stride,
+            "destination buffer is too small"
+        );
+        if row_bytes == 0 {
+            return;
+        }
+
+        for (dst_row, src_row) in dst.chunks_mut(dst_stride).zip(self.rows_iter()) {
+            match T::type_enum() {
+                PixelType::U8 => {
+                    for (dst, &src) in dst_row.iter_mut().zip(src_row) {
+                        *dst = u8::cast_from(src);
+                    }
+                }
+                PixelType::U16 => {
+                    for (dst, &src) in dst_row.chunks_exact_mut(2).zip(src_row) {
+                        dst.copy_from_slice(&u16::cast_from(src).to_le_bytes());
+                    }
+                }
+            }
+        }
+    }
+
     /// Copies data into the plane from a pixel array.
     ///
     /// # Panics
@@ -481,11 +909,14 @@ impl<T: Pixel> Plane<T> {
                     debug_assert!(T::type_enum() == PixelType::U16);
 
                     // SAFETY: because of the assert it is safe to assume that T == u16
-                    let self_row: &mut [u16] = unsafe { std::mem::transmute(self_row) };
+                    let self_row: &mut [u16] = unsafe { core::mem::transmute(self_row) };
                     // SAFETY: we reinterpret the slice of bytes as a slice of elements of
                     // [u8; 2] to allow for more efficient codegen with from_le_bytes
                     let source_row: &[[u8; 2]] = unsafe {
-                        std::slice::from_raw_parts(source_row.as_ptr().cast(), source_row.len() / 2)
+                        core::slice::from_raw_parts(
+                            source_row.as_ptr().cast(),
+                            source_row.len() / 2,
+                        )
                     };
 
                     for (self_pixel, bytes) in self_row.iter_mut().zip(source_row) {
@@ -528,7 +959,7 @@ impl<T: Pixel> Plane<T> {
                     // SAFETY: we reinterpret the slice of bytes as a slice
                     // of [u8; 2] with half the elements
                     let dest_row: &mut [[u8; 2]] = unsafe {
-                        std::slice::from_raw_parts_mut(
+                        core::slice::from_raw_parts_mut(
                             dest_row.as_mut_ptr().cast(),
                             dest_row.len() / 2,
                         )
@@ -548,6 +979,12 @@ impl<T: Pixel> Plane<T> {
     /// Downscaled with 2x2 box filter.
     /// Padded to dimensions with `frame_width` and `frame_height`.
     ///
+    /// This builds one level of the luma pyramid used for hierarchical motion
+    /// estimation. The padding of the result is half the padding of the
+    /// source, filled by replicating the edges. For odd dimensions the last
+    /// column or row is averaged with the padding of the source, which should
+    /// be extended first, e.g. with [`Plane::extend_padding`].
+    ///
     /// # Panics
     ///
     /// - If the requested width and height are > half the input width or height
@@ -556,8 +993,8 @@ impl<T: Pixel> Plane<T> {
         // SAFETY: all pixels initialized in this function
         let mut new = unsafe {
             Plane::new_uninitialized(
-                (src.cfg.width + 1) / 2,
-                (src.cfg.height + 1) / 2,
+                src.cfg.width.div_ceil(2),
+                src.cfg.height.div_ceil(2),
                 src.cfg.xdec + 1,
                 src.cfg.ydec + 1,
                 src.cfg.xpad / 2,
@@ -614,7 +1051,7 @@ impl<T: Pixel> Plane<T> {
     /// Downscales the source plane by a factor of `scale`, writing the result to `in_plane` (not padded)
     ///
     /// `in_plane`'s width and height must be sufficient for `scale`.
-    #[hawktracer(downscale_in_place)]
+    #[cfg_attr(feature = "tracing", hawktracer(downscale_in_place))]
     pub fn downscale_in_place<const SCALE: usize>(&self, in_plane: &mut Plane<T>) {
         // SAFETY: Bounds checks have been removed for performance reasons
         unsafe {
@@ -681,66 +1118,850 @@ impl<T: Pixel> Plane<T> {
         }
     }
 
-    /// Iterates over the pixels in the plane, skipping the padding.
-    pub fn iter(&self) -> PlaneIter<'_, T> {
-        PlaneIter::new(self)
+    /// Returns a plane downscaled from the source plane by `factor` (not padded)
+    ///
+    /// Each output pixel is the rounded average of a `factor` x `factor` box.
+    /// The output dimensions are rounded up, and the boxes on the right and
+    /// bottom edges only average the pixels they cover.
+    ///
+    /// # Panics
+    ///
+    /// - If `factor` is 0
+    pub fn downscale_n(&self, factor: usize) -> Plane<T> {
+        assert!(factor > 0, "downscale factor must be positive");
+
+        self.box_downscale(factor, factor, self.cfg.xdec, self.cfg.ydec)
     }
 
-    /// Iterates over the lines of the plane
-    pub fn rows_iter(&self) -> RowsIter<'_, T> {
-        RowsIter {
-            plane: self,
-            x: 0,
-            y: 0,
+    /// Returns a half-resolution plane (not padded), each output pixel being
+    /// the rounded average of a 2x2 block.
+    ///
+    /// Odd dimensions are rounded up, and the blocks on the right and bottom
+    /// edges only average the pixels they cover. The decimation of the new
+    /// plane is one more than the source plane on both axes.
+    pub fn downsample_420(&self) -> Plane<T> {
+        self.box_downscale(2, 2, self.cfg.xdec + 1, self.cfg.ydec + 1)
+    }
+
+    /// Averages `box_width` x `box_height` boxes into a new unpadded plane
+    /// with the given decimation.
+    pub(crate) fn box_downscale(
+        &self,
+        box_width: usize,
+        box_height: usize,
+        xdec: usize,
+        ydec: usize,
+    ) -> Plane<T> {
+        let width = self.cfg.width;
+        let height = self.cfg.height;
+        let mut new = Plane::new(
+            width.div_ceil(box_width),
+            height.div_ceil(box_height),
+            xdec,
+            ydec,
+            0,
+            0,
+        );
+
+        for (row_idx, dst_row) in new.rows_iter_mut().enumerate() {
+            let src_rows = row_idx * box_height..((row_idx + 1) * box_height).min(height);
+            for (col_idx, dst) in dst_row.iter_mut().enumerate() {
+                let src_cols = col_idx * box_width..((col_idx + 1) * box_width).min(width);
+                // u64 so that large boxes of high bit depth pixels do not overflow
+                let count = (src_rows.len() * src_cols.len()) as u64;
+                let mut sum = count / 2;
+                for y in src_rows.clone() {
+                    let src_row = &self.data[self.row_range_cropped(0, y as isize)];
+                    for &pixel in &src_row[src_cols.clone()] {
+                        sum += u64::from(pixel.to_u32());
+                    }
+                }
+                *dst = T::cast_from((sum / count) as u32);
+            }
         }
+
+        new
     }
 
-    pub fn rows_iter_mut(&mut self) -> RowsIterMut<'_, T> {
-        RowsIterMut {
-            plane: self as *mut Plane<T>,
-            x: 0,
-            y: 0,
-            phantom: PhantomData,
+    /// Returns a plane resampled to `new_width` x `new_height` with bilinear
+    /// interpolation (not padded)
+    ///
+    /// Pixel centers are aligned between the two planes, and samples outside
+    /// the source plane are clamped to its edges.
+    ///
+    /// # Panics
+    ///
+    /// - If the source plane is empty and the target is not
+    pub fn resize_bilinear(&self, new_width: usize, new_height: usize) -> Plane<T> {
+        let mut new = Plane::new(new_width, new_height, self.cfg.xdec, self.cfg.ydec, 0, 0);
+        if new_width == 0 || new_height == 0 {
+            return new;
         }
-    }
+        let width = self.cfg.width;
+        let height = self.cfg.height;
+        assert!(width > 0 && height > 0, "cannot resize an empty plane");
+
+        // Returns the two source positions to interpolate between, and the
+        // weight of the second one.
+        let taps = |dst: usize, dst_len: usize, src_len: usize| {
+            let pos = ((dst as f32 + 0.5) * src_len as f32 / dst_len as f32 - 0.5)
+                .clamp(0.0, (src_len - 1) as f32);
+            let p0 = pos as usize;
+            (p0, (p0 + 1).min(src_len - 1), pos - p0 as f32)
+        };
+        let x_taps: Vec<_> = (0..new_width).map(|x| taps(x, new_width, width)).collect();
 
-    /// Return a line
-    pub fn row(&self, y: isize) -> &[T] {
-        let range = self.row_range(0, y);
+        for (y, dst_row) in new.rows_iter_mut().enumerate() {
+            let (y0, y1, fy) = taps(y, new_height, height);
+            let row0 = &self.data[self.row_range_cropped(0, y0 as isize)];
+            let row1 = &self.data[self.row_range_cropped(0, y1 as isize)];
 
-        &self.data[range]
+            for (dst, &(x0, x1, fx)) in dst_row.iter_mut().zip(&x_taps) {
+                let top = fx.mul_add(row0[x1].to_f32() - row0[x0].to_f32(), row0[x0].to_f32());
+                let bottom = fx.mul_add(row1[x1].to_f32() - row1[x0].to_f32(), row1[x0].to_f32());
+                *dst = T::from_f32(fy.mul_add(bottom - top, top));
+            }
+        }
+
+        new
     }
-}
 
-/// Iterator over plane pixels, skipping padding.
-#[derive(Debug)]
-pub struct PlaneIter<'a, T: Pixel> {
-    plane: &'a Plane<T>,
-    y: usize,
-    x: usize,
-}
+    /// Returns a plane with twice the width and height, upsampled with
+    /// nearest-neighbor sampling (not padded)
+    pub fn upsample_2(&self) -> Plane<T> {
+        let mut new = Plane::new(
+            self.cfg.width * 2,
+            self.cfg.height * 2,
+            self.cfg.xdec.saturating_sub(1),
+            self.cfg.ydec.saturating_sub(1),
+            0,
+            0,
+        );
 
-impl<'a, T: Pixel> PlaneIter<'a, T> {
-    /// Creates a new iterator.
-    pub fn new(plane: &'a Plane<T>) -> Self {
-        Self { plane, y: 0, x: 0 }
-    }
+        for (row_idx, dst_row) in new.rows_iter_mut().enumerate() {
+            let src_row = &self.data[self.row_range_cropped(0, (row_idx / 2) as isize)];
+            for (dst, &src) in dst_row.chunks_exact_mut(2).zip(src_row) {
+                dst.fill(src);
+            }
+        }
 
-    fn width(&self) -> usize {
-        self.plane.cfg.width
+        new
     }
 
-    fn height(&self) -> usize {
-        self.plane.cfg.height
+    /// Returns a new unpadded plane with the rows and columns swapped.
+    ///
+    /// The decimation axes are swapped as well.
+    pub fn transpose(&self) -> Plane<T> {
+        let mut new = Plane::new(
+            self.cfg.height,
+            self.cfg.width,
+            self.cfg.ydec,
+            self.cfg.xdec,
+            0,
+            0,
+        );
+
+        for (y, dst_row) in new.rows_iter_mut().enumerate() {
+            for (x, dst) in dst_row.iter_mut().enumerate() {
+                *dst = self.p(y, x);
+            }
+        }
+
+        new
     }
-}
 
-impl<'a, T: Pixel> Iterator for PlaneIter<'a, T> {
-    type Item = T;
+    /// Returns a new unpadded plane holding the absolute difference between
+    /// the visible pixels of the two planes.
+    ///
+    /// # Panics
+    ///
+    /// - If the visible dimensions of the planes do not match
+    pub fn abs_diff(&self, other: &Plane<T>) -> Plane<T> {
+        assert!(
+            self.cfg.width == other.cfg.width && self.cfg.height == other.cfg.height,
+            "plane dimensions do not match ({}x{} != {}x{})",
+            self.cfg.width,
+            self.cfg.height,
+            other.cfg.width,
+            other.cfg.height
+        );
 
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        if self.y == self.height() {
-            return None;
+        let mut new = Plane::new(
+            self.cfg.width,
+            self.cfg.height,
+            self.cfg.xdec,
+            self.cfg.ydec,
+            0,
+            0,
+        );
+        let rows = self.rows_iter().zip(other.rows_iter());
+        for (dst_row, (a_row, b_row)) in new.rows_iter_mut().zip(rows) {
+            for (dst, (&a, &b)) in dst_row.iter_mut().zip(a_row.iter().zip(b_row)) {
+                *dst = T::cast_from(u32::cast_from(a).abs_diff(u32::cast_from(b)));
+            }
+        }
+
+        new
+    }
+
+    /// Computes the summed-area table of the visible pixels.
+    ///
+    /// The table has `(width + 1) * (height + 1)` entries in row-major
+    /// order, with a leading row and column of zeros, so that entry
+    /// `(x, y)` holds the sum of the pixels above and to the left of `(x, y)`.
+    pub fn integral_image(&self) -> Vec<u64> {
+        let table_width = self.cfg.width + 1;
+        let mut table = vec![0u64; table_width * (self.cfg.height + 1)];
+
+        for (y, row) in self.rows_iter().enumerate() {
+            let (prev, cur) = table[y * table_width..].split_at_mut(table_width);
+            let mut row_sum = 0u64;
+            for (x, &pixel) in row.iter().enumerate() {
+                row_sum += u32::cast_from(pixel) as u64;
+                cur[x + 1] = prev[x + 1] + row_sum;
+            }
+        }
+
+        table
+    }
+
+    /// Returns a new unpadded plane where each pixel is the rounded mean of
+    /// the `(2 * radius + 1)` square window centered on it.
+    ///
+    /// The window is clipped at the edges of the plane, so edge pixels
+    /// average fewer samples.
+    pub fn box_blur(&self, radius: usize) -> Plane<T> {
+        let PlaneConfig {
+            width,
+            height,
+            xdec,
+            ydec,
+            ..
+        } = self.cfg;
+        let table = self.integral_image();
+        let table_width = width + 1;
+        let mut new = Plane::new(width, height, xdec, ydec, 0, 0);
+
+        for (y, dst_row) in new.rows_iter_mut().enumerate() {
+            let y0 = y.saturating_sub(radius);
+            let y1 = (y + radius + 1).min(height);
+            for (x, dst) in dst_row.iter_mut().enumerate() {
+                let x0 = x.saturating_sub(radius);
+                let x1 = (x + radius + 1).min(width);
+                let sum = table[y1 * table_width + x1] + table[y0 * table_width + x0]
+                    - table[y0 * table_width + x1]
+                    - table[y1 * table_width + x0];
+                let count = ((x1 - x0) * (y1 - y0)) as u64;
+                *dst = T::cast_from(((sum + count / 2) / count) as u32);
+            }
+        }
+
+        new
+    }
+
+    /// Returns a new unpadded plane where each pixel is the median of the
+    /// `(2 * radius + 1)` square window centered on it.
+    ///
+    /// Positions outside the plane are clamped to its edges, so every window
+    /// holds the same number of samples. Each median is found with a partial
+    /// sort, which takes `O(width * height * radius^2)` time overall and is
+    /// meant for small radii.
+    pub fn median_filter(&self, radius: usize) -> Plane<T> {
+        let PlaneConfig {
+            width,
+            height,
+            xdec,
+            ydec,
+            ..
+        } = self.cfg;
+        let mut new = Plane::new(width, height, xdec, ydec, 0, 0);
+        let src = self.as_region_slice();
+        let side = 2 * radius + 1;
+        let mut window = Vec::with_capacity(side * side);
+
+        for (y, dst_row) in new.rows_iter_mut().enumerate() {
+            for (x, dst) in dst_row.iter_mut().enumerate() {
+                window.clear();
+                for wy in 0..side {
+                    let row = src.row((y + wy).saturating_sub(radius).min(height - 1));
+                    for wx in 0..side {
+                        window.push(row[(x + wx).saturating_sub(radius).min(width - 1)]);
+                    }
+                }
+                let mid = window.len() / 2;
+                *dst = *window.select_nth_unstable(mid).1;
+            }
+        }
+
+        new
+    }
+
+    /// Computes the sum of absolute differences between the visible pixels
+    /// of the two planes.
+    ///
+    /// # Panics
+    ///
+    /// - If the visible dimensions of the planes do not match
+    pub fn sad(&self, other: &Plane<T>) -> u64 {
+        assert!(
+            self.cfg.width == other.cfg.width && self.cfg.height == other.cfg.height,
+            "plane dimensions do not match ({}x{} != {}x{})",
+            self.cfg.width,
+            self.cfg.height,
+            other.cfg.width,
+            other.cfg.height
+        );
+
+        self.rows_iter()
+            .zip(other.rows_iter())
+            .map(|(a_row, b_row)| {
+                a_row
+                    .iter()
+                    .zip(b_row)
+                    .map(|(&a, &b)| u32::cast_from(a).abs_diff(u32::cast_from(b)) as u64)
+                    .sum::<u64>()
+            })
+            .sum()
+    }
+
+    /// Computes the mean SSIM against `other` over non-overlapping 8x8
+    /// windows.
+    ///
+    /// Windows on the right and bottom edges that do not fully fit in the
+    /// plane are skipped, so planes smaller than 8x8 return NaN. Variances and
+    /// covariance use the population (1/N) normalization.
+    ///
+    /// # Panics
+    ///
+    /// - If the visible dimensions of the planes do not match
+    pub fn ssim(&self, other: &Plane<T>, bit_depth: usize) -> f64 {
+        const WINDOW: usize = 8;
+
+        assert!(
+            self.cfg.width == other.cfg.width && self.cfg.height == other.cfg.height,
+            "plane dimensions do not match"
+        );
+
+        let max = ((1u64 << bit_depth) - 1) as f64;
+        let c1 = (0.01 * max) * (0.01 * max);
+        let c2 = (0.03 * max) * (0.03 * max);
+        let n = (WINDOW * WINDOW) as f64;
+
+        let mut total = 0.0;
+        let mut windows = 0;
+        for wy in (0..self.cfg.height / WINDOW).map(|y| y * WINDOW) {
+            for wx in (0..self.cfg.width / WINDOW).map(|x| x * WINDOW) {
+                let (mut sum_a, mut sum_b) = (0u64, 0u64);
+                let (mut sum_aa, mut sum_bb, mut sum_ab) = (0u64, 0u64, 0u64);
+                for y in wy..wy + WINDOW {
+                    let row_a = &self.data[self.row_range_cropped(wx as isize, y as isize)];
+                    let row_b = &other.data[other.row_range_cropped(wx as isize, y as isize)];
+                    for (&a, &b) in row_a[..WINDOW].iter().zip(&row_b[..WINDOW]) {
+                        let (a, b) = (u32::cast_from(a) as u64, u32::cast_from(b) as u64);
+                        sum_a += a;
+                        sum_b += b;
+                        sum_aa += a * a;
+                        sum_bb += b * b;
+                        sum_ab += a * b;
+                    }
+                }
+
+                let mean_a = sum_a as f64 / n;
+                let mean_b = sum_b as f64 / n;
+                let var_a = mean_a.mul_add(-mean_a, sum_aa as f64 / n);
+                let var_b = mean_b.mul_add(-mean_b, sum_bb as f64 / n);
+                let cov = mean_a.mul_add(-mean_b, sum_ab as f64 / n);
+
+                total += (2.0 * mean_a).mul_add(mean_b, c1) * 2.0f64.mul_add(cov, c2)
+                    / (mean_a.mul_add(mean_a, mean_b * mean_b) + c1)
+                    / (var_a + var_b + c2);
+                windows += 1;
+            }
+        }
+
+        total / windows as f64
+    }
+
+    /// Returns the visible pixels of the plane converted to `f32`, in
+    /// row-major order.
+    pub fn to_f32_vec(&self) -> Vec<f32> {
+        let mut out = Vec::with_capacity(self.cfg.width * self.cfg.height);
+        for row in self.rows_iter() {
+            out.extend(row.iter().map(|&p| p.to_f32()));
+        }
+        out
+    }
+
+    /// Computes the minimum, maximum and mean of the visible pixels in a
+    /// single pass.
+    ///
+    /// Returns `None` for empty planes, such as the chroma planes of a
+    /// monochrome frame.
+    pub fn stats(&self) -> Option<PlaneStats<T>> {
+        self.as_region_slice().stats()
+    }
+
+    /// Returns the mean of the visible pixels normalized to `0.0..=1.0` for
+    /// the given bit depth, or NaN if the plane is empty.
+    ///
+    /// The codewords are averaged as is, without any transfer function.
+    ///
+    /// # Panics
+    ///
+    /// - If `bit_depth` is 0 or larger than the size of `T` in bits
+    pub fn average(&self, bit_depth: usize) -> f64 {
+        assert!(
+            bit_depth > 0 && bit_depth <= T::BITS as usize,
+            "invalid bit depth ({}) for a {}-bit pixel",
+            bit_depth,
+            T::BITS
+        );
+        self.stats().map_or(f64::NAN, |stats| {
+            stats.mean / ((1u64 << bit_depth) - 1) as f64
+        })
+    }
+
+    /// Computes the CRC-32 of every visible row, so that corrupted rows can be
+    /// located cheaply.
+    ///
+    /// High bit depth samples are hashed in little-endian order. The padding
+    /// is not hashed, so planes with the same visible pixels produce the same
+    /// checksums.
+    pub fn row_checksums(&self) -> Vec<u32> {
+        self.rows_iter()
+            .map(|row| {
+                let crc = row.iter().fold(!0u32, |crc, &pixel| match T::type_enum() {
+                    PixelType::U8 => crc32_update(crc, &[u8::cast_from(pixel)]),
+                    PixelType::U16 => crc32_update(crc, &u16::cast_from(pixel).to_le_bytes()),
+                });
+                !crc
+            })
+            .collect()
+    }
+
+    /// Counts the occurrences of each sample value over the visible pixels.
+    ///
+    /// The returned vector has `1 << bit_depth` entries, which takes 256 KiB
+    /// for 16-bit content. Callers that only need coarse statistics should
+    /// bucket the pixels themselves instead.
+    ///
+    /// # Panics
+    ///
+    /// - If a pixel value does not fit in `bit_depth` bits
+    pub fn histogram(&self, bit_depth: usize) -> Vec<u32> {
+        let mut histogram = vec![0; 1 << bit_depth];
+        for row in self.rows_iter() {
+            for &pixel in row {
+                histogram[u32::cast_from(pixel) as usize] += 1;
+            }
+        }
+        histogram
+    }
+
+    /// Iterates over the pixels in the plane, skipping the padding.
+    ///
+    /// The pixels are yielded in row-major order.
+    pub fn iter(&self) -> PlaneIter<'_, T> {
+        PlaneIter::new(self)
+    }
+
+    /// Iterates over the lines of the plane
+    ///
+    /// Each row starts at the plane origin and is `width` pixels long, so the
+    /// padding is skipped.
+    pub fn rows_iter(&self) -> RowsIter<'_, T> {
+        RowsIter {
+            plane: self,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    /// Mutably iterates over the lines of the plane, skipping the padding.
+    pub fn rows_iter_mut(&mut self) -> RowsIterMut<'_, T> {
+        RowsIterMut {
+            plane: self as *mut Plane<T>,
+            x: 0,
+            y: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a read-only view of the visible region of the plane.
+    pub fn as_region_slice(&self) -> PlaneRegion<'_, T> {
+        let PlaneConfig {
+            stride,
+            width,
+            height,
+            ..
+        } = self.cfg;
+        let len = if height == 0 {
+            0
+        } else {
+            (height - 1) * stride + width
+        };
+
+        PlaneRegion {
+            data: &self.data_origin()[..len],
+            stride,
+            width,
+            height,
+        }
+    }
+
+    /// Returns a read-only view of the `width` x `height` rectangle at the
+    /// visible position (`x`, `y`), sharing the storage and the stride of the
+    /// plane.
+    ///
+    /// # Panics
+    ///
+    /// - If the rectangle exceeds the visible area of the plane
+    pub fn region(&self, x: usize, y: usize, width: usize, height: usize) -> PlaneRegion<'_, T> {
+        assert!(
+            x + width <= self.cfg.width && y + height <= self.cfg.height,
+            "region {}x{} at ({}, {}) exceeds the plane dimensions {}x{}",
+            width,
+            height,
+            x,
+            y,
+            self.cfg.width,
+            self.cfg.height
+        );
+
+        let stride = self.cfg.stride;
+        if width == 0 || height == 0 {
+            // The start of an empty region may lie past the end of the data.
+            return PlaneRegion {
+                data: &[],
+                stride,
+                width,
+                height,
+            };
+        }
+
+        PlaneRegion::new(&self.data_origin()[y * stride + x..], stride, width, height)
+    }
+
+    /// Returns a mutable view of the `width` x `height` rectangle at the
+    /// visible position (`x`, `y`).
+    ///
+    /// # Panics
+    ///
+    /// - If the rectangle exceeds the visible area of the plane
+    pub fn region_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> PlaneRegionMut<'_, T> {
+        assert!(
+            x + width <= self.cfg.width && y + height <= self.cfg.height,
+            "region {}x{} at ({}, {}) exceeds the plane dimensions {}x{}",
+            width,
+            height,
+            x,
+            y,
+            self.cfg.width,
+            self.cfg.height
+        );
+
+        let stride = self.cfg.stride;
+        if width == 0 || height == 0 {
+            // The start of an empty region may lie past the end of the data.
+            return PlaneRegionMut {
+                data: &mut [],
+                stride,
+                width,
+                height,
+            };
+        }
+
+        let len = (height - 1) * stride + width;
+        let start = y * stride + x;
+
+        PlaneRegionMut {
+            data: &mut self.data_origin_mut()[start..start + len],
+            stride,
+            width,
+            height,
+        }
+    }
+
+    /// Return a line
+    pub fn row(&self, y: isize) -> &[T] {
+        let range = self.row_range(0, y);
+
+        &self.data[range]
+    }
+}
+
+/// Read-only view of the visible region of a plane.
+///
+/// The rows are `stride` pixels apart, so the region is not contiguous unless
+/// `stride == width`.
+#[derive(Clone, Copy, Debug)]
+pub struct PlaneRegion<'a, T: Pixel> {
+    data: &'a [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T: Pixel> PlaneRegion<'a, T> {
+    /// Creates a view of `height` rows of `width` pixels, each starting
+    /// `stride` pixels after the previous one.
+    ///
+    /// # Panics
+    ///
+    /// - If `width` is larger than `stride`
+    /// - If `data` is too short to hold the region
+    pub fn new(data: &'a [T], stride: usize, width: usize, height: usize) -> Self {
+        assert!(width <= stride);
+        let len = if height == 0 {
+            0
+        } else {
+            (height - 1) * stride + width
+        };
+        assert!(data.len() >= len, "plane data is too short");
+
+        PlaneRegion {
+            data: &data[..len],
+            stride,
+            width,
+            height,
+        }
+    }
+
+    /// Returns a pointer to the first visible pixel.
+    pub fn as_ptr(&self) -> *const T {
+        self.data.as_ptr()
+    }
+
+    /// Distance between the start of two rows, in pixels.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Width of the region in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of the region in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the visible pixels of row `y`.
+    ///
+    /// # Panics
+    ///
+    /// - If `y` is not less than the height of the region
+    pub fn row(&self, y: usize) -> &'a [T] {
+        assert!(y < self.height);
+        if self.width == 0 {
+            return &[];
+        }
+        &self.data[y * self.stride..][..self.width]
+    }
+
+    /// Iterates over the rows of the region.
+    pub fn rows_iter(&self) -> impl ExactSizeIterator<Item = &'a [T]> {
+        let region = *self;
+        (0..self.height).map(move |y| region.row(y))
+    }
+
+    /// Computes the minimum, maximum and mean of the pixels in a single pass.
+    ///
+    /// Returns `None` for empty regions.
+    pub fn stats(&self) -> Option<PlaneStats<T>> {
+        let first = *self.rows_iter().next()?.first()?;
+        let mut min = first;
+        let mut max = first;
+        let mut sum = 0u64;
+        for row in self.rows_iter() {
+            for &pixel in row {
+                min = min.min(pixel);
+                max = max.max(pixel);
+                sum += u32::cast_from(pixel) as u64;
+            }
+        }
+
+        Some(PlaneStats {
+            min,
+            max,
+            mean: sum as f64 / (self.width * self.height) as f64,
+        })
+    }
+}
+
+impl Plane<u16> {
+    /// Returns the visible pixels as little-endian bytes, row after row.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_endian(Endianness::Little)
+    }
+
+    /// Returns the visible pixels as big-endian bytes, row after row.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_endian(Endianness::Big)
+    }
+
+    fn to_bytes_with_endian(&self, endian: Endianness) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.cfg.width * self.cfg.height * 2);
+        for row in self.rows_iter() {
+            for &pixel in row {
+                out.extend_from_slice(&endian.u16_to_bytes(pixel));
+            }
+        }
+        out
+    }
+}
+
+/// Lookup table for the CRC-32 (IEEE 802.3) polynomial, in reflected form.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+#[inline]
+fn crc32_update(crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Indexes the visible pixels by `(x, y)`.
+///
+/// See [`Plane::get`] for a non-panicking version.
+impl<T: Pixel> Index<(usize, usize)> for Plane<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.cfg.width && y < self.cfg.height,
+            "pixel ({}, {}) is outside the plane dimensions {}x{}",
+            x,
+            y,
+            self.cfg.width,
+            self.cfg.height
+        );
+        &self.data[Plane::index(self, x, y)]
+    }
+}
+
+/// Mutable view of a rectangle of a plane, sharing the stride of the plane.
+///
+/// Created by [`Plane::region_mut`].
+#[derive(Debug)]
+pub struct PlaneRegionMut<'a, T: Pixel> {
+    data: &'a mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Pixel> PlaneRegionMut<'_, T> {
+    /// Distance between the start of two rows, in pixels.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Width of the region in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of the region in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the pixels of row `y`.
+    ///
+    /// # Panics
+    ///
+    /// - If `y` is not less than the height of the region
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        assert!(y < self.height);
+        if self.width == 0 {
+            return &mut [];
+        }
+        &mut self.data[y * self.stride..][..self.width]
+    }
+
+    /// Iterates over the rows of the region.
+    pub fn rows_iter_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        let width = self.width;
+        let stride = self.stride;
+        let mut rest = &mut *self.data;
+        (0..self.height).map(move |_| {
+            // The last row is not followed by a full stride.
+            let len = stride.min(rest.len());
+            let (row, tail) = mem::take(&mut rest).split_at_mut(len);
+            rest = tail;
+            &mut row[..width]
+        })
+    }
+}
+
+/// Iterator over plane pixels, skipping padding.
+#[derive(Debug)]
+pub struct PlaneIter<'a, T: Pixel> {
+    plane: &'a Plane<T>,
+    y: usize,
+    x: usize,
+    // Set when the rows are not padded, so the visible pixels are contiguous.
+    contiguous: Option<core::slice::Iter<'a, T>>,
+}
+
+impl<'a, T: Pixel> PlaneIter<'a, T> {
+    /// Creates a new iterator.
+    pub fn new(plane: &'a Plane<T>) -> Self {
+        let contiguous = (plane.cfg.stride == plane.cfg.width)
+            .then(|| plane.data_origin()[..plane.cfg.width * plane.cfg.height].iter());
+
+        Self {
+            plane,
+            y: 0,
+            x: 0,
+            contiguous,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.plane.cfg.width
+    }
+
+    fn height(&self) -> usize {
+        self.plane.cfg.height
+    }
+}
+
+impl<'a, T: Pixel> Iterator for PlaneIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        if let Some(pixels) = &mut self.contiguous {
+            return pixels.next().copied();
+        }
+        if self.y == self.height() || self.width() == 0 {
+            return None;
         }
         let pixel = self.plane.p(self.x, self.y);
         if self.x == self.width() - 1 {
@@ -751,8 +1972,18 @@ impl<'a, T: Pixel> Iterator for PlaneIter<'a, T> {
         }
         Some(pixel)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if let Some(pixels) = &self.contiguous {
+            return pixels.size_hint();
+        }
+        let remaining = (self.height() - self.y) * self.width() - self.x;
+
+        (remaining, Some(remaining))
+    }
 }
 
+impl<T: Pixel> ExactSizeIterator for PlaneIter<'_, T> {}
 impl<T: Pixel> FusedIterator for PlaneIter<'_, T> {}
 
 // A Plane, PlaneSlice, or PlaneRegion is assumed to include or be able to include
@@ -1127,6 +2358,30 @@ pub mod test {
         assert_eq!(&expected[..], &v[..]);
     }
 
+    #[test]
+    fn test_plane_downsampled_padding() {
+        let mut plane = Plane::<u8>::new(7, 6, 0, 0, 8, 8);
+        plane.for_each_pixel_mut(|x, y, pixel| *pixel = (y * 16 + x * 2) as u8);
+        plane.extend_padding();
+
+        let downsampled = plane.downsampled(7, 6);
+        let cfg = &downsampled.cfg;
+        assert_eq!((cfg.width, cfg.height), (4, 3));
+        assert_eq!((cfg.xdec, cfg.ydec, cfg.xpad, cfg.ypad), (1, 1, 4, 4));
+        let rows: Vec<&[u8]> = downsampled.rows_iter().collect();
+        assert_eq!(rows, [[9, 13, 17, 20], [41, 45, 49, 52], [73, 77, 81, 84]]);
+
+        // every allocated row is the nearest visible row, with the edge
+        // pixels replicated to the left and right
+        let visible = cfg.xorigin..cfg.xorigin + cfg.width;
+        for (y, row) in downsampled.data.chunks_exact(cfg.stride).enumerate() {
+            let src_y = y.clamp(cfg.yorigin, cfg.yorigin + cfg.height - 1) - cfg.yorigin;
+            assert_eq!(&row[visible.clone()], rows[src_y]);
+            assert!(row[..cfg.xorigin].iter().all(|&p| p == rows[src_y][0]));
+            assert!(row[visible.end..].iter().all(|&p| p == rows[src_y][3]));
+        }
+    }
+
     #[test]
     fn test_plane_downscale() {
         #[rustfmt::skip]
@@ -1202,50 +2457,202 @@ pub mod test {
       3, 3
     ];
 
-        let v: Vec<_> = downscaled.iter().collect();
-        assert_eq!(&expected[..], &v[..]);
+        let v: Vec<_> = downscaled.iter().collect();
+        assert_eq!(&expected[..], &v[..]);
+    }
+
+    #[test]
+    fn test_plane_downscale_odd_2() {
+        #[rustfmt::skip]
+    let plane = Plane::<u8> {
+      data: PlaneData::from_slice(&[
+        9, 8, 3, 1, 0, 1, 4, 5, 0, 0,
+        0, 1, 4, 5, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 9, 0,
+        0, 2, 3, 6, 7, 0, 0, 0, 0, 0,
+        0, 0, 8, 9, 7, 5, 0, 0, 0, 0,
+        9, 8, 3, 1, 0, 1, 4, 5, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 2, 3, 6, 7, 0,
+        0, 0, 0, 0, 0, 0, 8, 9, 7, 5,
+        0, 0, 0, 0, 9, 8, 3, 1, 0, 0
+      ]),
+      cfg: PlaneConfig {
+        stride: 10,
+        alloc_height: 10,
+        width: 10,
+        height: 10,
+        xdec: 0,
+        ydec: 0,
+        xpad: 0,
+        ypad: 0,
+        xorigin: 0,
+        yorigin: 0,
+      },
+    };
+        let downscaled = plane.downscale::<3>();
+
+        #[rustfmt::skip]
+    let expected = &[
+      3, 1, 2,
+      4, 4, 1,
+      0, 0, 4,
+    ];
+
+        let v: Vec<_> = downscaled.iter().collect();
+        assert_eq!(&expected[..], &v[..]);
+    }
+
+    #[test]
+    fn test_plane_downscale_n() {
+        let mut plane = Plane::<u16>::new(5, 4, 0, 0, 0, 0);
+        #[rustfmt::skip]
+        let pixels: [u16; 20] = [
+            1, 2, 3, 4, 100,
+            5, 6, 7, 8, 200,
+            9, 9, 1, 1, 1000,
+            9, 9, 1, 1, 65535,
+        ];
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&pixels[y * 5..][..5]);
+        }
+
+        let exact = plane.downscale_n(1);
+        assert_eq!(exact.iter().collect::<Vec<_>>(), pixels);
+
+        let half = plane.downscale_n(2);
+        assert_eq!(half.cfg.width, 3);
+        assert_eq!(half.cfg.height, 2);
+        assert_eq!(half.iter().collect::<Vec<_>>(), [4, 6, 150, 9, 1, 33268]);
+
+        let ragged = plane.downscale_n(3);
+        assert_eq!(ragged.cfg.width, 2);
+        assert_eq!(ragged.cfg.height, 2);
+        assert_eq!(ragged.iter().collect::<Vec<_>>(), [5, 219, 6, 32768]);
+    }
+
+    #[test]
+    fn test_plane_downscale_n_large_factor() {
+        let mut plane = Plane::<u16>::new(300, 300, 0, 0, 0, 0);
+        plane.fill(u16::MAX);
+
+        let down = plane.downscale_n(300);
+        assert_eq!((down.cfg.width, down.cfg.height), (1, 1));
+        assert_eq!(down.iter().collect::<Vec<_>>(), [u16::MAX]);
+    }
+
+    #[test]
+    fn test_plane_upsample_2() {
+        let mut plane = Plane::<u8>::new(2, 2, 1, 1, 0, 0);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[2 * y as u8, 2 * y as u8 + 1]);
+        }
+
+        let up = plane.upsample_2();
+        assert_eq!(up.cfg.width, 4);
+        assert_eq!(up.cfg.height, 4);
+        assert_eq!((up.cfg.xdec, up.cfg.ydec), (0, 0));
+        #[rustfmt::skip]
+        assert_eq!(up.iter().collect::<Vec<_>>(), [
+            0, 0, 1, 1,
+            0, 0, 1, 1,
+            2, 2, 3, 3,
+            2, 2, 3, 3,
+        ]);
+    }
+
+    #[test]
+    fn test_plane_ssim() {
+        let mut a = Plane::<u8>::new(16, 16, 0, 0, 0, 0);
+        a.fill(100);
+        assert_eq!(a.ssim(&a.clone(), 8), 1.0);
+
+        let mut b = a.clone();
+        // Top-left window: uniform 110 against uniform 100
+        for row in b.rows_iter_mut().take(8) {
+            row[..8].fill(110);
+        }
+        // Top-right window: alternating 0/200 against uniform 100
+        for (y, row) in a.rows_iter_mut().enumerate().take(8) {
+            for (x, pixel) in row[8..].iter_mut().enumerate() {
+                *pixel = if (x + y) % 2 == 0 { 0 } else { 200 };
+            }
+        }
+
+        let c1 = (0.01f64 * 255.0).powi(2);
+        let c2 = (0.03f64 * 255.0).powi(2);
+        let luminance = (22000.0 + c1) / (22100.0 + c1);
+        let structure = c2 / (10000.0 + c2);
+        let expected = (luminance + structure + 2.0) / 4.0;
+
+        assert!((a.ssim(&b, 8) - expected).abs() < 1e-12);
+        assert!(Plane::<u8>::new(7, 7, 0, 0, 0, 0)
+            .ssim(&Plane::new(7, 7, 0, 0, 0, 0), 8)
+            .is_nan());
+    }
+
+    #[test]
+    fn test_plane_copy_from() {
+        let mut src = Plane::<u16>::new(6, 3, 0, 0, 0, 0);
+        for (y, row) in src.rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = (y * 6 + x) as u16;
+            }
+        }
+        let expected: Vec<u16> = (0..18).collect();
+
+        // Same layout
+        let mut dst = Plane::<u16>::new(6, 3, 0, 0, 0, 0);
+        dst.copy_from(&src);
+        assert_eq!(dst.iter().collect::<Vec<_>>(), expected);
+
+        // Different stride and origin
+        let mut dst = Plane::<u16>::new(6, 3, 0, 0, 40, 2);
+        dst.fill(0xffff);
+        dst.copy_from(&src);
+        assert_eq!(dst.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(dst.data[0], 0xffff);
+    }
+
+    #[test]
+    #[should_panic(expected = "plane dimensions do not match")]
+    fn test_plane_copy_from_mismatch() {
+        let src = Plane::<u8>::new(6, 3, 0, 0, 0, 0);
+        Plane::<u8>::new(6, 4, 0, 0, 0, 0).copy_from(&src);
+    }
+
+    #[test]
+    fn test_plane_to_f32_vec() {
+        let mut plane = Plane::<u16>::new(2, 2, 0, 0, 4, 4);
+        plane.fill(7);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[y as u16, 1000]);
+        }
+
+        assert_eq!(plane.to_f32_vec(), [0.0, 1000.0, 1.0, 1000.0]);
     }
 
     #[test]
-    fn test_plane_downscale_odd_2() {
-        #[rustfmt::skip]
-    let plane = Plane::<u8> {
-      data: PlaneData::from_slice(&[
-        9, 8, 3, 1, 0, 1, 4, 5, 0, 0,
-        0, 1, 4, 5, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 9, 0,
-        0, 2, 3, 6, 7, 0, 0, 0, 0, 0,
-        0, 0, 8, 9, 7, 5, 0, 0, 0, 0,
-        9, 8, 3, 1, 0, 1, 4, 5, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 2, 3, 6, 7, 0,
-        0, 0, 0, 0, 0, 0, 8, 9, 7, 5,
-        0, 0, 0, 0, 9, 8, 3, 1, 0, 0
-      ]),
-      cfg: PlaneConfig {
-        stride: 10,
-        alloc_height: 10,
-        width: 10,
-        height: 10,
-        xdec: 0,
-        ydec: 0,
-        xpad: 0,
-        ypad: 0,
-        xorigin: 0,
-        yorigin: 0,
-      },
-    };
-        let downscaled = plane.downscale::<3>();
+    fn test_plane_resize_bilinear() {
+        let mut plane = Plane::<u8>::new(2, 2, 0, 0, 0, 0);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[y as u8 * 100, y as u8 * 100 + 40]);
+        }
 
+        let up = plane.resize_bilinear(4, 4);
         #[rustfmt::skip]
-    let expected = &[
-      3, 1, 2,
-      4, 4, 1,
-      0, 0, 4,
-    ];
+        assert_eq!(up.iter().collect::<Vec<_>>(), [
+            0, 10, 30, 40,
+            25, 35, 55, 65,
+            75, 85, 105, 115,
+            100, 110, 130, 140,
+        ]);
 
-        let v: Vec<_> = downscaled.iter().collect();
-        assert_eq!(&expected[..], &v[..]);
+        let same = plane.resize_bilinear(2, 2);
+        assert_eq!(same.iter().collect::<Vec<_>>(), [0, 40, 100, 140]);
+
+        let down = up.resize_bilinear(1, 1);
+        assert_eq!(down.p(0, 0), 70);
     }
 
     #[test]
@@ -1295,6 +2702,653 @@ pub mod test {
     );
     }
 
+    #[test]
+    fn test_plane_pad_all_sides() {
+        let mut plane = Plane::<u8>::new(4, 4, 0, 0, 8, 8);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = (y * 4 + x) as u8;
+            }
+        }
+        plane.pad(4, 4);
+
+        let PlaneConfig {
+            stride,
+            alloc_height,
+            xorigin,
+            yorigin,
+            ..
+        } = plane.cfg;
+        assert!(xorigin >= 8 && yorigin == 8);
+        assert!(stride >= xorigin + 4 + 8 && alloc_height == 20);
+
+        // Top-left, top-right, bottom-left and bottom-right corners
+        assert_eq!(plane.data[0], 0);
+        assert_eq!(plane.data[stride - 1], 3);
+        assert_eq!(plane.data[(alloc_height - 1) * stride], 12);
+        assert_eq!(plane.data[alloc_height * stride - 1], 15);
+
+        // Left and right edges
+        for y in 0..4 {
+            let row = &plane.data[(yorigin + y) * stride..][..stride];
+            assert!(row[..xorigin].iter().all(|&p| p == (y * 4) as u8));
+            assert!(row[xorigin + 4..].iter().all(|&p| p == (y * 4 + 3) as u8));
+        }
+
+        // Top and bottom edges
+        for y in 0..yorigin {
+            assert_eq!(
+                &plane.data[y * stride..][..stride],
+                &plane.data[yorigin * stride..][..stride]
+            );
+            assert_eq!(
+                &plane.data[(yorigin + 4 + y) * stride..][..stride],
+                &plane.data[(yorigin + 3) * stride..][..stride]
+            );
+        }
+    }
+
+    #[test]
+    fn test_rows_iter() {
+        let mut plane = Plane::<u16>::new(5, 3, 0, 0, 4, 4);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.fill(y as u16);
+        }
+
+        let rows = plane.rows_iter();
+        assert_eq!(rows.size_hint(), (3, Some(3)));
+        assert_eq!(rows.len(), 3);
+
+        let rows: Vec<_> = plane.rows_iter().collect();
+        assert_eq!(rows, [&[0; 5][..], &[1; 5][..], &[2; 5][..]]);
+    }
+
+    #[test]
+    fn test_plane_accessors() {
+        let plane = Plane::<u16>::new(5, 3, 1, 1, 2, 2);
+        assert_eq!(plane.width(), 5);
+        assert_eq!(plane.height(), 3);
+        assert_eq!(plane.stride(), plane.cfg.stride);
+
+        let origin = plane.cfg.yorigin * plane.stride() + plane.cfg.xorigin;
+        assert_eq!(plane.data_origin().len(), plane.data.len() - origin);
+    }
+
+    #[test]
+    fn test_pixel_iterator_count() {
+        for (width, height) in [(7, 5), (1, 3), (0, 4), (4, 0)] {
+            let plane = Plane::<u16>::new(width, height, 0, 0, 3, 3);
+            assert_eq!(plane.iter().len(), width * height);
+            assert_eq!(plane.iter().count(), width * height);
+        }
+    }
+
+    #[test]
+    fn test_pixel_iterator_len_after_nth() {
+        let plane = Plane::<u8>::new(4, 4, 0, 0, 2, 3);
+        let mut iter = plane.iter();
+        assert_eq!(iter.len(), 16);
+        iter.nth(5);
+        assert_eq!(iter.len(), 10);
+    }
+
+    #[test]
+    fn test_plane_histogram() {
+        let mut plane = Plane::<u8>::new(4, 2, 0, 0, 2, 2);
+        plane.fill(9);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[0, 1, 1, 3 + y as u8]);
+        }
+
+        let histogram = plane.histogram(8);
+        assert_eq!(histogram.len(), 256);
+        assert_eq!(&histogram[..5], &[2, 4, 0, 1, 1]);
+        assert_eq!(histogram.iter().sum::<u32>(), 8);
+    }
+
+    #[test]
+    fn test_plane_downsample_420() {
+        let mut plane = Plane::<u8>::new(3, 3, 0, 0, 0, 0);
+        for (row, src) in plane
+            .rows_iter_mut()
+            .zip([[1, 2, 9], [4, 6, 10], [7, 8, 20]])
+        {
+            row.copy_from_slice(&src);
+        }
+
+        let downsampled = plane.downsample_420();
+        assert_eq!((downsampled.cfg.xdec, downsampled.cfg.ydec), (1, 1));
+        let rows: Vec<_> = downsampled.rows_iter().collect();
+        // (1 + 2 + 4 + 6) / 4, (9 + 10) / 2, (7 + 8) / 2 and 20, rounded
+        assert_eq!(rows, [[3, 10], [8, 20]]);
+    }
+
+    #[test]
+    fn test_plane_get_set() {
+        let mut plane = Plane::<u16>::new(3, 2, 0, 0, 2, 2);
+        plane.fill(0);
+        plane.set(2, 1, 500);
+
+        assert_eq!(plane.get(2, 1), Some(500));
+        assert_eq!(plane[(2, 1)], 500);
+        assert_eq!(plane[(0, 0)], 0);
+        assert_eq!(plane.get(3, 0), None);
+        assert_eq!(plane.get(0, 2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the plane dimensions")]
+    fn test_plane_set_out_of_bounds() {
+        let mut plane = Plane::<u8>::new(3, 2, 0, 0, 2, 2);
+        plane.set(3, 0, 1);
+    }
+
+    #[test]
+    fn test_plane_region_mut() {
+        let mut plane = Plane::<u8>::new(4, 4, 0, 0, 4, 4);
+        plane.fill(0);
+
+        let mut region = plane.region_mut(1, 2, 3, 2);
+        assert_eq!((region.width(), region.height()), (3, 2));
+        region.row_mut(0)[0] = 1;
+        for row in region.rows_iter_mut() {
+            row[2] = 2;
+        }
+
+        let rows: Vec<_> = plane.rows_iter().collect();
+        assert_eq!(
+            rows,
+            [[0, 0, 0, 0], [0, 0, 0, 0], [0, 1, 0, 2], [0, 0, 0, 2]]
+        );
+    }
+
+    #[test]
+    fn test_plane_region_mut_empty() {
+        let mut plane = Plane::<u8>::new(6, 5, 0, 0, 0, 0);
+
+        let mut bottom = plane.region_mut(3, 5, 2, 0);
+        assert_eq!((bottom.width(), bottom.height()), (2, 0));
+        assert_eq!(bottom.rows_iter_mut().count(), 0);
+
+        let mut column = plane.region_mut(6, 0, 0, 5);
+        assert!(column.row_mut(4).is_empty());
+        assert!(column.rows_iter_mut().all(|row| row.is_empty()));
+        assert_eq!(column.rows_iter_mut().count(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the plane dimensions")]
+    fn test_plane_region_mut_out_of_bounds() {
+        let mut plane = Plane::<u8>::new(4, 4, 0, 0, 4, 4);
+        plane.region_mut(2, 0, 3, 1);
+    }
+
+    #[test]
+    fn test_plane_abs_diff() {
+        let mut a = Plane::<u16>::new(3, 1, 0, 0, 2, 2);
+        let mut b = Plane::<u16>::new(3, 1, 0, 0, 0, 0);
+        a.rows_iter_mut()
+            .next()
+            .unwrap()
+            .copy_from_slice(&[0, 65535, 7]);
+        b.rows_iter_mut()
+            .next()
+            .unwrap()
+            .copy_from_slice(&[65535, 0, 7]);
+
+        let diff = a.abs_diff(&b);
+        assert_eq!(diff.rows_iter().next().unwrap(), [65535, 65535, 0]);
+        assert_eq!(diff, b.abs_diff(&a));
+    }
+
+    #[test]
+    fn test_plane_copy_to_aligned() {
+        let mut plane = Plane::<u8>::new(3, 2, 0, 0, 2, 2);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[y as u8, 1, 2]);
+        }
+        let mut dst = [0xffu8; 8];
+        plane.copy_to_aligned(&mut dst, 4);
+        assert_eq!(dst, [0, 1, 2, 0xff, 1, 1, 2, 0xff]);
+
+        let mut plane = Plane::<u16>::new(2, 2, 0, 0, 0, 0);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[0x0102, 0x0300 + y as u16]);
+        }
+        let mut dst = [0u8; 16];
+        plane.copy_to_aligned(&mut dst, 8);
+        assert_eq!(dst, [2, 1, 0, 3, 0, 0, 0, 0, 2, 1, 1, 3, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "destination buffer is too small")]
+    fn test_plane_copy_to_aligned_short_buffer() {
+        let plane = Plane::<u16>::new(2, 2, 0, 0, 0, 0);
+        plane.copy_to_aligned(&mut [0; 12], 8);
+    }
+
+    #[test]
+    fn test_plane_average() {
+        let mut plane = Plane::<u16>::new(2, 1, 0, 0, 0, 0);
+        plane
+            .rows_iter_mut()
+            .next()
+            .unwrap()
+            .copy_from_slice(&[0, 1023]);
+
+        assert_eq!(plane.average(10), 0.5);
+        assert!(Plane::<u8>::new(0, 0, 0, 0, 0, 0).average(8).is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid bit depth")]
+    fn test_plane_average_bit_depth_too_large() {
+        Plane::<u8>::new(2, 1, 0, 0, 0, 0).average(64);
+    }
+
+    #[test]
+    fn test_plane_integral_image() {
+        let mut plane = Plane::<u16>::new(2, 2, 0, 0, 2, 2);
+        for (row, src) in plane.rows_iter_mut().zip([[1, 2], [3, 65535]]) {
+            row.copy_from_slice(&src);
+        }
+
+        assert_eq!(plane.integral_image(), [0, 0, 0, 0, 1, 3, 0, 4, 65541]);
+    }
+
+    #[test]
+    fn test_plane_box_blur() {
+        let (width, height) = (7, 5);
+        let mut plane = Plane::<u8>::new(width, height, 0, 0, 0, 0);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = ((x * 37 + y * 91) % 256) as u8;
+            }
+        }
+
+        let blurred = plane.box_blur(1);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0u32;
+                let mut count = 0u32;
+                for sy in y.saturating_sub(1)..(y + 2).min(height) {
+                    for sx in x.saturating_sub(1)..(x + 2).min(width) {
+                        sum += plane.p(sx, sy) as u32;
+                        count += 1;
+                    }
+                }
+                assert_eq!(blurred.p(x, y) as u32, (sum + count / 2) / count);
+            }
+        }
+        assert_eq!(plane.box_blur(0), plane);
+    }
+
+    #[test]
+    fn test_plane_iter_contiguous() {
+        let mut unpadded = Plane::<u8>::new(64, 2, 0, 0, 0, 0);
+        let mut padded = Plane::<u8>::new(64, 2, 0, 0, 2, 2);
+        assert_eq!(unpadded.cfg.stride, 64);
+        for plane in [&mut unpadded, &mut padded] {
+            for (y, row) in plane.rows_iter_mut().enumerate() {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    *pixel = (x + y) as u8;
+                }
+            }
+        }
+
+        let mut iter = unpadded.iter();
+        assert_eq!(iter.len(), 128);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.len(), 127);
+        assert!(unpadded.iter().eq(padded.iter()));
+        assert_eq!(unpadded.iter().nth(64), Some(1));
+    }
+
+    #[test]
+    fn test_plane_row_checksums() {
+        let mut a = Plane::<u8>::new(9, 2, 0, 0, 0, 0);
+        let mut b = Plane::<u8>::new(9, 2, 0, 0, 4, 4);
+        a.fill(0);
+        b.fill(0xff);
+        for plane in [&mut a, &mut b] {
+            for (y, row) in plane.rows_iter_mut().enumerate() {
+                row.copy_from_slice(if y == 0 { b"123456789" } else { b"abcdefghi" });
+            }
+        }
+
+        // CRC-32 check value of "123456789"
+        assert_eq!(a.row_checksums()[0], 0xcbf4_3926);
+        assert_eq!(a.row_checksums(), b.row_checksums());
+        b.set(8, 1, 0);
+        assert_eq!(a.row_checksums()[0], b.row_checksums()[0]);
+        assert_ne!(a.row_checksums()[1], b.row_checksums()[1]);
+
+        let mut wide = Plane::<u16>::new(1, 1, 0, 0, 0, 0);
+        wide.set(0, 0, 0x3231);
+        assert_eq!(wide.row_checksums(), [0x4f53_44cd]);
+    }
+
+    #[test]
+    fn test_plane_sad() {
+        let mut a = Plane::<u16>::new(3, 2, 0, 0, 2, 2);
+        let mut b = Plane::<u16>::new(3, 2, 0, 0, 0, 0);
+        a.fill(0);
+        b.fill(65535);
+        b.set(1, 1, 5);
+
+        assert_eq!(a.sad(&b), 5 * 65535 + 5);
+        assert_eq!(b.sad(&a), a.sad(&b));
+        assert_eq!(a.sad(&a), 0);
+    }
+
+    #[test]
+    fn test_plane_byte_order() {
+        let mut plane = Plane::<u16>::new(2, 1, 0, 0, 4, 4);
+        plane.fill(0);
+        plane.set(0, 0, 0x0102);
+        plane.set(1, 0, 0x03ff);
+
+        assert_eq!(plane.to_le_bytes(), [0x02, 0x01, 0xff, 0x03]);
+        assert_eq!(plane.to_be_bytes(), [0x01, 0x02, 0x03, 0xff]);
+    }
+
+    #[test]
+    fn test_plane_transpose() {
+        let mut plane = Plane::<u8>::new(3, 2, 1, 0, 0, 0);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = (y * 3 + x) as u8;
+            }
+        }
+
+        let transposed = plane.transpose();
+        assert_eq!((transposed.cfg.width, transposed.cfg.height), (2, 3));
+        assert_eq!((transposed.cfg.xdec, transposed.cfg.ydec), (0, 1));
+        let rows: Vec<_> = transposed.rows_iter().collect();
+        assert_eq!(rows, [[0, 3], [1, 4], [2, 5]]);
+    }
+
+    #[test]
+    fn test_plane_stats() {
+        let mut plane = Plane::<u16>::new(3, 2, 0, 0, 2, 2);
+        plane.fill(0);
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[10, 20 + y as u16, 1000]);
+        }
+
+        assert_eq!(
+            plane.stats(),
+            Some(PlaneStats {
+                min: 10,
+                max: 1000,
+                mean: 2061.0 / 6.0,
+            })
+        );
+        assert_eq!(Plane::<u8>::new(0, 0, 0, 0, 0, 0).stats(), None);
+    }
+
+    #[test]
+    fn test_plane_offset_beyond_type_size() {
+        let mut plane = Plane::<u8>::new(2, 1, 0, 0, 0, 0);
+        plane.set(0, 0, 200);
+        plane.set(1, 0, 250);
+
+        plane.offset(100, 10);
+        assert_eq!(plane.rows_iter().next().unwrap(), [255, 255]);
+        plane.offset(-5, 32);
+        assert_eq!(plane.rows_iter().next().unwrap(), [250, 250]);
+    }
+
+    #[test]
+    fn test_plane_offset() {
+        let mut plane = Plane::<u16>::new(3, 1, 0, 0, 2, 2);
+        plane.fill(7);
+        plane.set(0, 0, 0);
+        plane.set(1, 0, 500);
+        plane.set(2, 0, 1020);
+
+        plane.offset(10, 10);
+        assert_eq!(plane.rows_iter().next().unwrap(), [10, 510, 1023]);
+        plane.offset(-600, 10);
+        assert_eq!(plane.rows_iter().next().unwrap(), [0, 0, 423]);
+        plane.offset(i32::MIN, 10);
+        assert_eq!(plane.rows_iter().next().unwrap(), [0, 0, 0]);
+        plane.offset(i32::MAX, 10);
+        assert_eq!(plane.rows_iter().next().unwrap(), [1023, 1023, 1023]);
+        // the padding is left untouched
+        assert_eq!(plane.data[0], 7);
+
+        let mut plane = Plane::<u8>::new(2, 1, 0, 0, 0, 0);
+        plane.set(0, 0, 250);
+        plane.set(1, 0, 3);
+        plane.offset(-4, 8);
+        assert_eq!(plane.rows_iter().next().unwrap(), [246, 0]);
+        plane.offset(20, 8);
+        assert_eq!(plane.rows_iter().next().unwrap(), [255, 20]);
+    }
+
+    #[test]
+    fn test_plane_apply_lut() {
+        let mut plane = Plane::<u16>::new(4, 1, 0, 0, 2, 2);
+        plane.fill(1023);
+        for x in 0..4 {
+            plane.set(x, 0, x as u16 * 300);
+        }
+
+        let invert: Vec<u16> = (0..1024).rev().collect();
+        plane.apply_lut(&invert);
+        assert_eq!(plane.rows_iter().next().unwrap(), [1023, 723, 423, 123]);
+        // the padding is left untouched
+        assert_eq!(plane.data[0], 1023);
+
+        let posterize: Vec<u16> = (0..1024).map(|v| v & !255).collect();
+        plane.apply_lut(&posterize);
+        assert_eq!(plane.rows_iter().next().unwrap(), [768, 512, 256, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of the lookup table")]
+    fn test_plane_apply_lut_out_of_range() {
+        let mut plane = Plane::<u16>::new(4, 4, 0, 0, 0, 0);
+        plane.fill(256);
+        plane.apply_lut(&[0; 256]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid lookup table length")]
+    fn test_plane_apply_lut_invalid_length() {
+        let mut plane = Plane::<u8>::new(4, 4, 0, 0, 0, 0);
+        plane.apply_lut(&[0; 255]);
+    }
+
+    #[test]
+    fn test_plane_region() {
+        let mut plane = Plane::<u8>::new(6, 5, 0, 0, 4, 4);
+        for y in 0..5 {
+            for x in 0..6 {
+                plane.set(x, y, (y * 10 + x) as u8);
+            }
+        }
+
+        let region = plane.region(2, 1, 3, 2);
+        assert_eq!(region.stride(), plane.cfg.stride);
+        assert_eq!((region.width(), region.height()), (3, 2));
+        let rows: Vec<&[u8]> = region.rows_iter().collect();
+        assert_eq!(rows, [&[12, 13, 14][..], &[22, 23, 24][..]]);
+
+        assert_eq!(plane.region(6, 5, 0, 0).rows_iter().len(), 0);
+        let full = plane.region(0, 0, 6, 5);
+        assert_eq!(full.row(4), plane.as_region_slice().row(4));
+    }
+
+    #[test]
+    fn test_plane_region_empty() {
+        let plane = Plane::<u8>::new(6, 5, 0, 0, 0, 0);
+
+        let corner = plane.region(6, 5, 0, 0);
+        assert_eq!((corner.width(), corner.height()), (0, 0));
+        assert_eq!(corner.rows_iter().len(), 0);
+        assert!(corner.stats().is_none());
+
+        let column = plane.region(6, 1, 0, 4);
+        let rows: Vec<&[u8]> = column.rows_iter().collect();
+        assert_eq!(rows, [&[0u8; 0][..]; 4]);
+        assert_eq!(plane.region(2, 5, 3, 0).rows_iter().len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the plane dimensions")]
+    fn test_plane_region_out_of_bounds() {
+        let plane = Plane::<u8>::new(6, 5, 0, 0, 4, 4);
+        plane.region(4, 0, 3, 1);
+    }
+
+    #[test]
+    fn test_plane_swap() {
+        let mut a = Plane::<u8>::new(4, 4, 0, 0, 0, 0);
+        let mut b = Plane::<u8>::new(4, 4, 0, 0, 8, 8);
+        a.fill(1);
+        b.fill(2);
+        for row in a.rows_iter_mut().take(2) {
+            row.fill(9);
+        }
+        let (a_cfg, b_cfg) = (a.cfg.clone(), b.cfg.clone());
+
+        a.swap(&mut b);
+        assert_eq!(a.cfg, b_cfg);
+        assert_eq!(b.cfg, a_cfg);
+        assert!(a.iter().all(|p| p == 2));
+        let rows: Vec<&[u8]> = b.rows_iter().collect();
+        assert_eq!(rows, [[9; 4], [9; 4], [1; 4], [1; 4]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "plane dimensions do not match")]
+    fn test_plane_swap_mismatch() {
+        let mut a = Plane::<u8>::new(4, 4, 0, 0, 0, 0);
+        let mut b = Plane::<u8>::new(4, 2, 0, 0, 0, 0);
+        a.swap(&mut b);
+    }
+
+    #[test]
+    fn test_plane_for_each_pixel_mut() {
+        let mut plane = Plane::<u8>::new(3, 2, 0, 0, 2, 2);
+        plane.fill(200);
+        let mut visited = 0;
+        plane.for_each_pixel_mut(|x, y, pixel| {
+            assert_eq!(*pixel, 200);
+            *pixel = (y * 10 + x) as u8;
+            visited += 1;
+        });
+
+        assert_eq!(visited, 6);
+        let rows: Vec<&[u8]> = plane.rows_iter().collect();
+        assert_eq!(rows, [[0, 1, 2], [10, 11, 12]]);
+        assert_eq!(
+            plane.data.iter().filter(|&&p| p == 200).count(),
+            plane.data.len() - 6
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")]
+    fn test_plane_serialize_visible() {
+        let mut plane = Plane::<u16>::new(3, 2, 1, 0, 8, 8);
+        plane.fill(7);
+        plane.for_each_pixel_mut(|x, y, pixel| *pixel = (y * 3 + x) as u16);
+
+        let json = serde_json::to_string(&plane).unwrap();
+        assert_eq!(
+            json,
+            r#"{"width":3,"height":2,"xdec":1,"ydec":0,"data":[0,1,2,3,4,5]}"#
+        );
+
+        let round_trip: Plane<u16> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.cfg.xdec, 1);
+        assert_eq!(round_trip.cfg.xpad, 0);
+        assert!(round_trip.rows_iter().eq(plane.rows_iter()));
+        assert_ne!(round_trip, plane);
+
+        let truncated = r#"{"width":3,"height":2,"xdec":0,"ydec":0,"data":[0,1,2]}"#;
+        assert!(serde_json::from_str::<Plane<u16>>(truncated).is_err());
+    }
+
+    #[test]
+    fn test_plane_is_uniform() {
+        let mut plane = Plane::<u16>::new(4, 3, 0, 0, 2, 2);
+        plane.fill(0);
+        for row in plane.rows_iter_mut() {
+            row.fill(512);
+        }
+        assert_eq!(plane.is_uniform(), Some(512));
+
+        plane.set(3, 2, 513);
+        assert_eq!(plane.is_uniform(), None);
+        plane.set(3, 2, 512);
+        plane.set(0, 0, 511);
+        assert_eq!(plane.is_uniform(), None);
+
+        assert_eq!(Plane::<u8>::new(0, 0, 0, 0, 0, 0).is_uniform(), None);
+    }
+
+    #[test]
+    fn test_plane_memory() {
+        let plane = Plane::<u16>::new(10, 4, 0, 0, 0, 0);
+        assert_eq!(plane.visible_bytes(), 80);
+        // rows are aligned to 32 pixels
+        assert_eq!(plane.allocated_bytes(), 32 * 4 * 2);
+        assert_eq!(plane.allocated_bytes(), plane.data.len() * 2);
+
+        let padded = Plane::<u8>::new(64, 4, 0, 0, 8, 2);
+        assert_eq!(padded.visible_bytes(), 256);
+        assert_eq!(padded.allocated_bytes(), 192 * 8);
+    }
+
+    #[test]
+    fn test_plane_median_filter() {
+        let mut plane = Plane::<u16>::new(5, 4, 0, 0, 2, 2);
+        plane.for_each_pixel_mut(|x, _, pixel| *pixel = 100 + x as u16);
+        plane.set(2, 1, 1023);
+        plane.set(0, 3, 0);
+
+        let filtered = plane.median_filter(1);
+        assert_eq!(filtered.cfg.xpad, 0);
+        let rows: Vec<&[u16]> = filtered.rows_iter().collect();
+        for row in &rows {
+            assert_eq!(row, &[100, 101, 102, 103, 104]);
+        }
+
+        assert!(plane.median_filter(0).rows_iter().eq(plane.rows_iter()));
+        assert_eq!(plane.median_filter(3).p(2, 1), 102);
+    }
+
+    #[test]
+    fn test_plane_fill() {
+        let mut plane = Plane::<u8>::new(4, 4, 0, 0, 2, 2);
+        plane.fill(42);
+        assert!(plane.data.iter().all(|&p| p == 42));
+
+        plane.clear();
+        assert!(plane.data.iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn test_region_slice() {
+        let mut plane = Plane::<u8>::new(3, 2, 0, 0, 2, 2);
+        plane.clear();
+        for (y, row) in plane.rows_iter_mut().enumerate() {
+            row.copy_from_slice(&[y as u8 + 1; 3]);
+        }
+
+        let region = plane.as_region_slice();
+        assert_eq!(region.width(), 3);
+        assert_eq!(region.height(), 2);
+        assert_eq!(region.stride(), plane.cfg.stride);
+        assert_eq!(region.as_ptr(), plane.data_origin().as_ptr());
+        assert_eq!(region.row(0), &[1, 1, 1]);
+        assert_eq!(region.row(1), &[2, 2, 2]);
+    }
+
     #[test]
     fn test_pixel_iterator() {
         #[rustfmt::skip]
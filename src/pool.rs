@@ -0,0 +1,292 @@
+// Copyright (c) 2026, The rav1e contributors. All rights reserved
+//
+// This source code is subject to the terms of the BSD 2 Clause License and
+// the Alliance for Open Media Patent License 1.0. If the BSD 2 Clause License
+// was not distributed with this source code in the LICENSE file, you can
+// obtain it at www.aomedia.org/license/software. If the Alliance for Open
+// Media Patent License 1.0 was not distributed with this source code in the
+// PATENTS file, you can obtain it at www.aomedia.org/license/patent.
+
+use crate::frame::Frame;
+use crate::pixel::{ChromaSampling, Pixel};
+use crate::plane::PlaneConfig;
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// A cache of frames with the same geometry, to avoid reallocating a frame
+/// for every picture of a stream.
+///
+/// Frames handed out by [`FramePool::get`] are given back with
+/// [`FramePool::recycle`] once they are no longer needed, and are reused by
+/// the following calls to `get` with the same parameters. Requesting another
+/// geometry drops the cached frames.
+#[derive(Debug)]
+pub struct FramePool<T: Pixel> {
+    frames: Vec<Frame<T>>,
+    cfgs: Option<[PlaneConfig; 3]>,
+    max_frames: usize,
+    allocations: usize,
+}
+
+impl<T: Pixel> FramePool<T> {
+    /// Creates a new pool that keeps at most `max_frames` recycled frames.
+    pub const fn new(max_frames: usize) -> Self {
+        FramePool {
+            frames: Vec::new(),
+            cfgs: None,
+            max_frames,
+            allocations: 0,
+        }
+    }
+
+    /// Returns a frame with the same geometry as
+    /// `Frame::new_with_padding(width, height, chroma_sampling, luma_padding)`.
+    ///
+    /// The frame is taken from the pool if possible, in which case the
+    /// contents of its planes are left over from its previous use.
+    ///
+    /// # Panics
+    ///
+    /// - If a new frame is needed and it is too large or the allocation fails
+    /// - In debug builds, if a new frame is needed and `width` or `height` is
+    ///   not a multiple of the chroma subsampling
+    pub fn get(
+        &mut self,
+        width: usize,
+        height: usize,
+        chroma_sampling: ChromaSampling,
+        luma_padding: usize,
+    ) -> Frame<T> {
+        let cfgs = match Frame::<T>::plane_configs(width, height, chroma_sampling, luma_padding) {
+            Ok(cfgs) => cfgs,
+            Err(e) => panic!("{}", e),
+        };
+        if self.cfgs.as_ref() != Some(&cfgs) {
+            self.frames.clear();
+            self.cfgs = Some(cfgs);
+        }
+
+        self.frames.pop().unwrap_or_else(|| {
+            self.allocations += 1;
+            Frame::new_with_padding(width, height, chroma_sampling, luma_padding)
+        })
+    }
+
+    /// Returns a frame to the pool.
+    ///
+    /// The frame is dropped if it does not have the geometry of the frames
+    /// last requested with [`FramePool::get`], or if the pool is full.
+    pub fn recycle(&mut self, frame: Frame<T>) {
+        let matches = self.cfgs.as_ref().is_some_and(|cfgs| {
+            frame
+                .planes
+                .iter()
+                .zip(cfgs)
+                .all(|(plane, cfg)| plane.cfg == *cfg)
+        });
+        if matches && self.frames.len() < self.max_frames {
+            self.frames.push(frame);
+        }
+    }
+
+    /// Returns the number of frames currently cached.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if no frames are cached.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the number of frames allocated by [`FramePool::get`] since the
+    /// pool was created.
+    pub const fn allocations(&self) -> usize {
+        self.allocations
+    }
+}
+
+/// A sliding window over the most recent frames of a stream, for temporal
+/// filters.
+///
+/// Pushing a frame into a full buffer evicts the oldest one. Evicted frames
+/// are recycled into the [`FramePool`] of the buffer, if any, so that they
+/// can be reused for the following frames.
+#[derive(Debug)]
+pub struct FrameBuffer<T: Pixel> {
+    frames: VecDeque<Frame<T>>,
+    capacity: usize,
+    pool: Option<FramePool<T>>,
+}
+
+impl<T: Pixel> FrameBuffer<T> {
+    /// Creates a new buffer holding up to `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        FrameBuffer {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+            pool: None,
+        }
+    }
+
+    /// Creates a new buffer holding up to `capacity` frames, which recycles
+    /// the evicted frames into `pool`.
+    pub fn with_pool(capacity: usize, pool: FramePool<T>) -> Self {
+        FrameBuffer {
+            pool: Some(pool),
+            ..Self::new(capacity)
+        }
+    }
+
+    /// Adds `frame` as the newest frame, evicting the oldest frame if the
+    /// buffer is full.
+    ///
+    /// A buffer with a capacity of 0 evicts `frame` immediately.
+    pub fn push(&mut self, frame: Frame<T>) {
+        if self.capacity == 0 {
+            self.evict(frame);
+            return;
+        }
+        if self.frames.len() == self.capacity {
+            if let Some(oldest) = self.frames.pop_back() {
+                self.evict(oldest);
+            }
+        }
+        self.frames.push_front(frame);
+    }
+
+    fn evict(&mut self, frame: Frame<T>) {
+        if let Some(pool) = self.pool.as_mut() {
+            pool.recycle(frame);
+        }
+    }
+
+    /// Returns the frame pushed `offset` frames before the newest one, so
+    /// that `get(0)` is the newest frame.
+    pub fn get(&self, offset: usize) -> Option<&Frame<T>> {
+        self.frames.get(offset)
+    }
+
+    /// Returns the number of frames in the buffer.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if the buffer holds no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the largest number of frames held by the buffer.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the pool the evicted frames are recycled into, to allocate
+    /// the next frames from it.
+    pub fn pool_mut(&mut self) -> Option<&mut FramePool<T>> {
+        self.pool.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reuses_recycled_frames() {
+        let mut pool = FramePool::<u16>::new(2);
+        let a = pool.get(64, 48, ChromaSampling::Cs420, 16);
+        let b = pool.get(64, 48, ChromaSampling::Cs420, 16);
+        assert_eq!(pool.allocations(), 2);
+        assert_eq!(
+            a,
+            Frame::new_with_padding(64, 48, ChromaSampling::Cs420, 16)
+        );
+
+        pool.recycle(a);
+        pool.recycle(b);
+        assert_eq!(pool.len(), 2);
+        for _ in 0..10 {
+            let frame = pool.get(64, 48, ChromaSampling::Cs420, 16);
+            pool.recycle(frame);
+        }
+        assert_eq!(pool.allocations(), 2);
+
+        pool.recycle(Frame::new_with_padding(64, 48, ChromaSampling::Cs420, 16));
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn discards_mismatched_frames() {
+        let mut pool = FramePool::<u8>::new(4);
+        pool.recycle(Frame::new(64, 48, ChromaSampling::Cs420));
+        assert!(pool.is_empty());
+
+        let frame = pool.get(64, 48, ChromaSampling::Cs420, 0);
+        pool.recycle(Frame::new(64, 48, ChromaSampling::Cs444));
+        pool.recycle(Frame::new_with_padding(64, 48, ChromaSampling::Cs420, 8));
+        assert!(pool.is_empty());
+        pool.recycle(frame);
+        assert_eq!(pool.len(), 1);
+
+        let other = pool.get(32, 32, ChromaSampling::Cs420, 0);
+        assert!(pool.is_empty());
+        assert_eq!(other.planes[0].cfg.width, 32);
+        assert_eq!(pool.allocations(), 2);
+    }
+
+    fn numbered(n: u8) -> Frame<u8> {
+        let mut frame = Frame::new(8, 8, ChromaSampling::Cs420);
+        frame.fill(n);
+        frame
+    }
+
+    #[test]
+    fn buffer_wraps_around() {
+        let mut buffer = FrameBuffer::new(3);
+        assert!(buffer.is_empty());
+        assert!(buffer.get(0).is_none());
+
+        for n in 0..5 {
+            buffer.push(numbered(n));
+        }
+        assert_eq!(buffer.len(), 3);
+        for offset in 0..3 {
+            assert_eq!(
+                buffer.get(offset).unwrap().planes[0].p(0, 0),
+                4 - offset as u8
+            );
+        }
+        assert!(buffer.get(3).is_none());
+    }
+
+    #[test]
+    fn buffer_capacity_edge_cases() {
+        let mut empty = FrameBuffer::new(0);
+        empty.push(numbered(0));
+        assert!(empty.is_empty());
+        assert!(empty.get(0).is_none());
+
+        let mut single = FrameBuffer::new(1);
+        single.push(numbered(1));
+        single.push(numbered(2));
+        assert_eq!(single.len(), 1);
+        assert_eq!(single.get(0).unwrap().planes[0].p(0, 0), 2);
+    }
+
+    #[test]
+    fn buffer_recycles_evicted_frames() {
+        let mut buffer = FrameBuffer::<u8>::with_pool(2, FramePool::new(1));
+        for _ in 0..10 {
+            let frame = buffer
+                .pool_mut()
+                .unwrap()
+                .get(8, 8, ChromaSampling::Cs420, 0);
+            buffer.push(frame);
+        }
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.pool_mut().unwrap().allocations(), 3);
+    }
+}